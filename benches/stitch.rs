@@ -0,0 +1,80 @@
+//! Timing harness for the stitching path, runnable without a display:
+//! `cargo bench`. Reports the best of several runs per case.
+
+use capture::stitch::{detect_overlap, stitch_images};
+use capture::{CaptureOptions, MockSource, ScreenCapture, ScrollDirection};
+use image::{Rgba, RgbaImage};
+use std::time::{Duration, Instant};
+
+const RUNS: usize = 5;
+
+fn page(width: u32, height: u32) -> RgbaImage {
+    RgbaImage::from_fn(width, height, |x, y| {
+        Rgba([(x ^ y) as u8, (y % 251) as u8, (x % 241) as u8, 255])
+    })
+}
+
+fn frames(page: &RgbaImage, frame_height: u32, step: u32) -> Vec<RgbaImage> {
+    (0..=page.height() - frame_height)
+        .step_by(step as usize)
+        .map(|top| image::imageops::crop_imm(page, 0, top, page.width(), frame_height).to_image())
+        .collect()
+}
+
+fn bench(name: &str, mut run: impl FnMut()) {
+    let best = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            run();
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or(Duration::ZERO);
+    println!("{:<40} {:>10.2?}", name, best);
+}
+
+fn main() {
+    // A long full-HD capture: 30 frames with a 125px overlap
+    let (hd_width, hd_height, hd_overlap) = (1920, 1080, 125);
+    let hd_page = page(hd_width, 29 * (hd_height - hd_overlap) + hd_height);
+    let hd_frames = frames(&hd_page, hd_height, hd_height - hd_overlap);
+    let hd_overlaps = vec![hd_overlap; hd_frames.len() - 1];
+    bench("stitch_images (30 frames of 1920x1080)", || {
+        stitch_images(&hd_frames, &hd_overlaps, ScrollDirection::Vertical, false);
+    });
+    bench("stitch_images (30 frames, horizontal)", || {
+        stitch_images(&hd_frames, &hd_overlaps, ScrollDirection::Horizontal, false);
+    });
+
+    let (width, frame_height, step) = (1280, 720, 600);
+    let page = page(width, 20 * step + frame_height);
+    let frames = frames(&page, frame_height, step);
+    let overlaps = vec![frame_height - step; frames.len() - 1];
+    println!(
+        "{} frames of {}x{}, {}px overlap",
+        frames.len(),
+        width,
+        frame_height,
+        frame_height - step
+    );
+
+    bench("stitch_images (hard cut)", || {
+        stitch_images(&frames, &overlaps, ScrollDirection::Vertical, false);
+    });
+    bench("stitch_images (seam blend)", || {
+        stitch_images(&frames, &overlaps, ScrollDirection::Vertical, true);
+    });
+    bench("detect_overlap (one pair)", || {
+        detect_overlap(&frames[0], &frames[1], frame_height / 2);
+    });
+    bench("capture_from_source (full loop)", || {
+        let source = MockSource::new(frames.clone());
+        let options = CaptureOptions::new()
+            .with_overlap(frame_height - step)
+            .with_delay(0)
+            .with_scroll_delay(0);
+        ScreenCapture::new()
+            .capture_from_source(&source, &options)
+            .unwrap();
+    });
+}
@@ -10,13 +10,43 @@ pub mod gui {
     pub const LOG_HEIGHT_EMPTY: f32 = 50.0;
     pub const LOG_HEIGHT_WITH_CONTENT: f32 = 150.0;
 
+    // Region selection overlay
+    pub const OVERLAY_DIM_ALPHA: u8 = 80;
+    pub const OVERLAY_MIN_SELECTION: f32 = 2.0;
+
+    // Live preview thumbnail bounds
+    pub const PREVIEW_MAX_WIDTH: u32 = 320;
+    pub const PREVIEW_MAX_HEIGHT: u32 = 240;
+
+    // How long the "Copied!" confirmation stays visible
+    pub const COPY_FEEDBACK_MS: u64 = 1500;
+
+    // Countdown before a capture checks Stop and updates its status this often
+    pub const COUNTDOWN_TICK_MS: u64 = 100;
+
+    // Global start/stop hotkey, and how often its events are checked while
+    // the window is in the background
+    pub const DEFAULT_HOTKEY: &str = "F9";
+    pub const HOTKEY_POLL_MS: u64 = 100;
+
     // Slider ranges
     pub const OVERLAP_MIN: u32 = 50;
     pub const OVERLAP_MAX: u32 = 500;
+    pub const OVERLAP_PERCENT_MIN: f32 = 1.0;
+    pub const OVERLAP_PERCENT_MAX: f32 = 50.0;
+    pub const DEFAULT_OVERLAP_PERCENT: f32 = 12.0;
     pub const DELAY_MIN: u64 = 0;
     pub const DELAY_MAX: u64 = 10;
     pub const SCROLL_DELAY_MIN: u64 = 100;
     pub const SCROLL_DELAY_MAX: u64 = 1000;
+    pub const PIXEL_TOLERANCE_MIN: u8 = 0;
+    pub const PIXEL_TOLERANCE_MAX: u8 = 64;
+    pub const DIFF_THRESHOLD_MIN: f32 = 0.0;
+    pub const DIFF_THRESHOLD_MAX: f32 = 5.0;
+    pub const QUALITY_MIN: u8 = 0;
+    pub const QUALITY_MAX: u8 = 100;
+    pub const SCALE_MIN: f32 = 0.1;
+    pub const SCALE_MAX: f32 = 2.0;
 
     // Default font paths
     pub const DEFAULT_FONT_PATHS: &[&str] =
@@ -29,6 +59,15 @@ pub mod gui {
             None
         }
     }
+
+    // Saved GUI settings, restored on the next launch
+    pub fn get_config_file_path() -> Option<String> {
+        if let Ok(home) = std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")) {
+            Some(format!("{}/.config/capture/gui-config.json", home))
+        } else {
+            None
+        }
+    }
 }
 
 // Capture configuration defaults
@@ -38,6 +77,19 @@ pub mod defaults {
     pub const DELAY: u64 = 3;
     pub const SCROLL_DELAY: u64 = 200;
     pub const MAX_SCROLLS_DEFAULT: &str = "";
+    pub const PIXEL_TOLERANCE: u8 = 0;
+    pub const QUALITY: u8 = 90;
+    // Luma cutoff for bilevel output, midway between black and white
+    pub const BILEVEL_THRESHOLD: u8 = 128;
+    // Two scrolls that reveal nothing new end the capture, even when a
+    // blinking caret or hover state keeps whole frames from matching
+    pub const STALL_LIMIT: usize = 2;
+    pub const TIMELAPSE_INTERVAL_MS: u64 = 1000;
+    pub const TIMELAPSE_COUNT: usize = 10;
+    // Final image scale factor (--scale); 1.0 keeps the stitched size
+    pub const SCALE: f32 = 1.0;
+    // Longest side of the --preview-preset thumbnail
+    pub const PREVIEW_THUMBNAIL_SIZE: u32 = 480;
 
     pub const CROP_X: i32 = 0;
     pub const CROP_Y: i32 = 0;
@@ -45,11 +97,128 @@ pub mod defaults {
     pub const CROP_HEIGHT: i32 = 1080;
 }
 
+// Display geometry
+pub mod display {
+    // Windows DPI at 100% scaling; window rects of DPI-unaware processes are
+    // in these logical units
+    pub const BASE_DPI: u32 = 96;
+
+    // Distance in points within which a picked corner snaps to a window edge
+    pub const SNAP_DISTANCE: i32 = 12;
+}
+
 // Capture timing constants
 pub mod timing {
     pub const SCROLL_WAIT_MS: u64 = 500;
+    // Pause between the chords of a --key sequence
+    pub const KEY_SEQUENCE_GAP_MS: u64 = 50;
     pub const SMALL_DELAY_MS: u64 = 300;
     pub const MOUSE_POSITION_POLL_MS: u64 = 100;
     pub const ZOOM_ENABLE_DELAY_MS: u64 = 500;
     pub const KEYBOARD_POLL_MS: u64 = 500;
+    pub const PROGRESS_TICK_MS: u64 = 100;
+    pub const PAUSE_POLL_MS: u64 = 100;
+    pub const OSASCRIPT_TIMEOUT_MS: u64 = 3000;
+    pub const OSASCRIPT_POLL_MS: u64 = 50;
+    // Redraw time after parking the cursor for --hide-cursor
+    pub const CURSOR_HIDE_MS: u64 = 30;
+    pub const SCREEN_RETRY_ATTEMPTS: u32 = 3;
+    pub const SCREEN_RETRY_DELAY_MS: u64 = 500;
+    // Re-captures of a blank frame (e.g. right after an app switch)
+    pub const BLANK_FRAME_RETRIES: u32 = 2;
+    pub const BLANK_FRAME_RETRY_DELAY_MS: u64 = 200;
+    // Screen polling interval and default ceiling for --adaptive-delay
+    pub const SETTLE_POLL_MS: u64 = 50;
+    pub const MAX_SETTLE_MS: u64 = 2000;
+}
+
+// Programmatic scrolling
+pub mod scroll {
+    // Approximate pixel distance of one mouse wheel click on platforms
+    // without pixel-precise scroll events
+    pub const PIXELS_PER_WHEEL_CLICK: u32 = 40;
+
+    // Wheel clicks sent left to reach the start of a horizontal page,
+    // which has no Home key equivalent
+    pub const HOME_WHEEL_CLICKS: i32 = 500;
+}
+
+// PDF export
+pub mod pdf {
+    // A4 paper in millimeters; the image width is scaled to the page width
+    pub const A4_WIDTH_MM: f32 = 210.0;
+    pub const A4_HEIGHT_MM: f32 = 297.0;
+    pub const MM_PER_INCH: f32 = 25.4;
+}
+
+// Splitting tall results (--max-height)
+pub mod split {
+    // Rows each part repeats from the end of the one before, so a line cut
+    // at the bottom of one part shows whole at the top of the next
+    pub const SEAM_HEIGHT: u32 = 100;
+}
+
+// Text recognition (--ocr)
+pub mod ocr {
+    // Rows handed to Tesseract at a time; whole long captures exceed its
+    // 32767px image limit
+    pub const SLICE_HEIGHT: u32 = 8000;
+    pub const DEFAULT_LANG: &str = "eng";
+}
+
+// Similarity detection
+pub mod similarity {
+    // Maximum percentage of differing pixels for two bands to count as similar
+    pub const DIFF_THRESHOLD_PERCENTAGE: f32 = 0.5;
+
+    // Adaptive mode scales the threshold between these factors, from
+    // near-blank content (any diff matters) to busy content (noise dominates)
+    pub const ADAPTIVE_MIN_SCALE: f32 = 0.1;
+    pub const ADAPTIVE_MAX_SCALE: f32 = 4.0;
+
+    // Entropy of a uniformly distributed 8-bit luma histogram
+    pub const MAX_ENTROPY_BITS: f32 = 8.0;
+
+    // Bytes each rayon task compares in images_are_identical; large enough
+    // to amortize scheduling, small enough to stop soon after a mismatch
+    pub const IDENTICAL_CHUNK_BYTES: usize = 64 * 1024;
+
+    // Channel value up to which a pixel still counts as black in is_blank
+    pub const BLANK_TOLERANCE: u8 = 8;
+
+    // Default minimum structural similarity for `--metric ssim`
+    pub const SSIM_THRESHOLD: f32 = 0.98;
+    // Side of the square windows SSIM statistics are computed over
+    pub const SSIM_WINDOW: u32 = 8;
+    // Stabilizing constants from the SSIM paper: (0.01 * 255)^2, (0.03 * 255)^2
+    pub const SSIM_C1: f64 = 6.5025;
+    pub const SSIM_C2: f64 = 58.5225;
+}
+
+// Sticky header/footer detection
+pub mod sticky {
+    // Fraction of consecutive frame pairs in which a row must stay unchanged
+    pub const DEFAULT_AGREEMENT: f32 = 0.8;
+    // Header and footer are each capped at this fraction of the frame height,
+    // so blank page margins aren't mistaken for a fixed bar
+    pub const MAX_BAND_FRACTION: f32 = 0.33;
+}
+
+// Uniform border removal (--autotrim)
+pub mod autotrim {
+    // Channel difference from the edge color still counted as border
+    pub const DEFAULT_TOLERANCE: u8 = 8;
+    // An axis losing more than this fraction is left alone, so a mostly
+    // blank page isn't cropped down to its few non-blank pixels
+    pub const MAX_TRIM_FRACTION: f32 = 0.4;
+}
+
+// Overlap detection between consecutive frames
+pub mod overlap_detection {
+    // Smallest overlap considered, so tiny offsets can't win by chance
+    pub const MIN_OVERLAP: u32 = 10;
+    // Compare every Nth column to keep the sweep fast
+    pub const SAMPLE_STRIDE: usize = 4;
+    // Mean per-channel difference above which a match is rejected
+    pub const MAX_MEAN_DIFF: f32 = 8.0;
 }
@@ -0,0 +1,131 @@
+//! Frame-by-frame scroll capture for callers that stitch on their own, e.g.
+//! an app that shows each frame as it arrives. See [`ScreenCapture::frames`].
+
+use crate::source::{CaptureSource, ScreenSource};
+use crate::{CaptureError, CaptureOptions, ScreenCapture, ScrollState, constants::timing};
+use image::RgbaImage;
+use std::thread;
+use std::time::Duration;
+
+/// Scrolls and captures one frame per call to `next`. The first frame is
+/// taken before any scroll; each later one is a step of the same loop
+/// [`ScreenCapture::capture_with_scroll`] runs, so the iterator ends where
+/// that capture would stop: at the end of the content, at the scroll or
+/// time limit, when the stop flag is raised, or after the first error.
+/// Frames that didn't move are skipped unless the frame count is exact.
+/// Dropping it stops the capture, keeping the frames already taken.
+pub struct Frames<'a> {
+    capture: &'a ScreenCapture,
+    options: CaptureOptions,
+    // Resolved on the first call, after the start delay
+    source: Option<Box<dyn CaptureSource + 'a>>,
+    state: Option<ScrollState>,
+    previous: Option<RgbaImage>,
+    finished: bool,
+}
+
+impl<'a> Frames<'a> {
+    pub(crate) fn new(
+        capture: &'a ScreenCapture,
+        options: CaptureOptions,
+        source: Option<Box<dyn CaptureSource + 'a>>,
+    ) -> Self {
+        Self {
+            capture,
+            options,
+            source,
+            state: None,
+            previous: None,
+            finished: false,
+        }
+    }
+
+    /// Scrolls performed so far
+    pub fn scroll_count(&self) -> usize {
+        self.state.as_ref().map_or(0, |state| state.scroll_count)
+    }
+
+    /// Waits out the start delay, resolves the crop and takes the first frame
+    fn first_frame(&mut self) -> anyhow::Result<RgbaImage> {
+        let mut crop_region = None;
+        if self.source.is_none() {
+            thread::sleep(Duration::from_secs(self.options.delay));
+            crop_region = self.capture.resolve_crop_region(
+                self.options.window_only,
+                self.options.crop.clone(),
+                &None,
+            )?;
+            self.source = Some(Box::new(ScreenSource::new(self.capture, crop_region)));
+        }
+        let source = self.source.as_deref().unwrap();
+
+        let (state, frame) = self.capture.start_scroll(
+            source,
+            &self.options,
+            crop_region,
+            self.capture.stop_flag.clone(),
+            &None,
+        )?;
+        self.state = Some(state);
+        Ok(frame)
+    }
+
+    /// Scrolls until the content moves and returns that frame, or `None`
+    /// once the capture should stop
+    fn next_frame(&mut self, previous: &RgbaImage) -> anyhow::Result<Option<RgbaImage>> {
+        let source = self.source.as_deref().unwrap();
+        let state = self.state.as_mut().unwrap();
+
+        loop {
+            let Some(step) = self.capture.scroll_step(source, state, previous, &None)? else {
+                return Ok(None);
+            };
+            if source.is_live() {
+                thread::sleep(Duration::from_millis(timing::SMALL_DELAY_MS));
+            }
+            // Stalled frames repeat the previous one, as in the scroll loop
+            if !step.stalled || self.capture.exact_frames.is_some() {
+                return Ok(Some(step.image));
+            }
+        }
+    }
+}
+
+impl Iterator for Frames<'_> {
+    type Item = std::result::Result<RgbaImage, CaptureError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        if self
+            .capture
+            .stop_flag
+            .as_ref()
+            .is_some_and(|flag| *flag.lock().unwrap())
+        {
+            self.finished = true;
+            return None;
+        }
+
+        let result = match self.previous.take() {
+            None => self.first_frame().map(Some),
+            Some(previous) => self.next_frame(&previous),
+        };
+
+        match result {
+            Ok(Some(frame)) => {
+                self.previous = Some(frame.clone());
+                Some(Ok(frame))
+            }
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = true;
+                Some(Err(e.into()))
+            }
+        }
+    }
+}
@@ -1,39 +1,42 @@
-use crate::constants::{defaults, gui as gui_const};
+use crate::constants::{defaults, display, gui as gui_const, similarity};
+use crate::{CaptureOptions, ScrollKey};
 use eframe::egui;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::thread;
 
-#[derive(Clone, Copy, PartialEq)]
-enum ScrollKey {
-    Space,
-    Down,
-    PageDown,
-}
-
-impl ScrollKey {
-    fn as_str(&self) -> &str {
-        match self {
-            ScrollKey::Space => "space",
-            ScrollKey::Down => "down",
-            ScrollKey::PageDown => "pagedown",
-        }
-    }
-}
-
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)] // Fields missing from older config files fall back to defaults
 struct CaptureConfig {
     output_filename: String, // Filename without extension
     output_format: String,   // File format (png, jpg, etc.)
+    quality: u8,             // Encoding quality for lossy formats
+    scale: f32,              // Resize factor for the saved image
+    auto_name: bool,         // Don't overwrite an existing output file
+    grayscale: bool,
+    bilevel: bool, // Save black and white, cut at `threshold`
+    threshold: u8, // Luma cutoff for bilevel output
     overlap: u32,
+    auto_overlap: bool, // Detect each pair's overlap; `overlap` is the fallback
+    overlap_in_percent: bool, // Use `overlap_percent` of the frame height instead of `overlap`
+    overlap_percent: f32,
     delay: u64,
     scroll_key: ScrollKey,
 
     // Screenshot mode settings
     max_scrolls: String, // Empty string means unlimited
+    min_scrolls: usize,  // Scrolls before end-of-page detection may stop
     scroll_delay: u64,
+    pixel_tolerance: u8,
+    diff_threshold_enabled: bool, // Also end when frames differ by at most `diff_threshold`
+    diff_threshold: f32,          // Percent of pixels that may differ between "same" frames
+    #[serde(skip)]
+    dry_run: bool, // Scroll and log stats without saving
+    dump_frames: String,          // Directory for raw frames, empty to disable
 
     // Crop settings
+    display: usize, // Index of the display to capture
     window_only: bool,
     crop_enabled: bool,
     use_preset: bool,
@@ -46,6 +49,10 @@ struct CaptureConfig {
     // Font settings
     font_path: String,
 
+    // Global hotkey that starts or stops a capture, e.g. "F9" or
+    // "ctrl+shift+KeyS"; empty disables it
+    hotkey: String,
+
     // UI settings
     status_color: [u8; 3], // RGB color values
 }
@@ -55,11 +62,27 @@ impl Default for CaptureConfig {
         Self {
             output_filename: "00".to_string(), // Just the filename without extension
             output_format: "png".to_string(),  // Default format
+            quality: defaults::QUALITY,
+            scale: defaults::SCALE,
+            auto_name: false,
+            grayscale: false,
+            bilevel: false,
+            threshold: defaults::BILEVEL_THRESHOLD,
             overlap: defaults::OVERLAP,
+            auto_overlap: false,
+            overlap_in_percent: false,
+            overlap_percent: gui_const::DEFAULT_OVERLAP_PERCENT,
             delay: defaults::DELAY,
             scroll_key: ScrollKey::Space,
             max_scrolls: defaults::MAX_SCROLLS_DEFAULT.to_string(),
+            min_scrolls: 0,
             scroll_delay: defaults::SCROLL_DELAY,
+            pixel_tolerance: defaults::PIXEL_TOLERANCE,
+            diff_threshold_enabled: false,
+            diff_threshold: similarity::DIFF_THRESHOLD_PERCENTAGE,
+            dry_run: false,
+            dump_frames: String::new(),
+            display: 0,
             window_only: false,
             crop_enabled: false,
             use_preset: false,
@@ -69,54 +92,157 @@ impl Default for CaptureConfig {
             crop_width: defaults::CROP_WIDTH,
             crop_height: defaults::CROP_HEIGHT,
             font_path: String::new(),
+            hotkey: gui_const::DEFAULT_HOTKEY.to_string(),
             status_color: [255, 255, 0], // Yellow by default
         }
     }
 }
 
+impl CaptureConfig {
+    /// Loads the saved GUI settings, falling back to defaults if the file
+    /// is missing or unreadable
+    fn load() -> Self {
+        gui_const::get_config_file_path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let path = gui_const::get_config_file_path()
+            .ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
+        if let Some(dir) = std::path::Path::new(&path).parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(&path, content)?;
+        Ok(())
+    }
+}
+
 #[derive(Clone)]
 enum CaptureStatus {
     Idle,
-    Running(String),   // Status message
+    Running {
+        msg: String,
+        progress: Option<f32>, // Fraction of max_scrolls done, when limited
+    },
     Completed(String), // Result message
     Error(String),
 }
 
+impl CaptureStatus {
+    fn running(msg: String) -> Self {
+        CaptureStatus::Running {
+            msg,
+            progress: None,
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq)]
 enum Tab {
     Capture,
     Settings,
 }
 
+/// A font file read on a background thread, tagged with the request it
+/// answers so a cancelled or superseded load is ignored
+struct LoadedFont {
+    generation: u64,
+    path: String,
+    data: std::io::Result<Vec<u8>>,
+}
+
+/// Fields of the Settings tab's "Add preset" form
+struct NewPresetForm {
+    name: String,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+impl Default for NewPresetForm {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            x: defaults::CROP_X,
+            y: defaults::CROP_Y,
+            width: defaults::CROP_WIDTH,
+            height: defaults::CROP_HEIGHT,
+        }
+    }
+}
+
 pub struct CaptureApp {
     current_tab: Tab,
     config: CaptureConfig,
     status: Arc<Mutex<CaptureStatus>>,
     is_running: Arc<Mutex<bool>>,
     should_stop: Arc<Mutex<bool>>,
+    should_pause: Arc<Mutex<bool>>,
     logs: Arc<Mutex<Vec<String>>>,
-    presets: HashMap<String, String>,
+    presets: HashMap<String, crate::presets::Preset>,
     preset_names: Vec<String>,
+    custom_presets: HashMap<String, crate::presets::Preset>, // Edited in Settings, written on Save
+    custom_presets_dirty: bool,
+    new_preset: NewPresetForm,
+    preset_status: String,
+    displays: Vec<String>,
     font_status: String,
+    font_generation: u64, // Bumped per load; results of older loads are dropped
+    font_loading: bool,
+    loaded_font: Arc<Mutex<Option<LoadedFont>>>, // Filled by the reader thread
+    picked_font_path: Arc<Mutex<Option<String>>>, // Filled by the file dialog
+    cli_command: String,
+    cli_copied_at: Option<std::time::Instant>,
+    last_result: Arc<Mutex<Option<image::RgbaImage>>>, // Stitched image of the last capture
+    image_copied_at: Option<std::time::Instant>,
+    preview: Arc<Mutex<Option<egui::ColorImage>>>, // Latest frame from the capture thread
+    preview_texture: Option<egui::TextureHandle>,
+    // Unregisters the hotkey when dropped, so it lives as long as the app
+    hotkey_manager: Option<global_hotkey::GlobalHotKeyManager>,
+    registered_hotkey: Option<global_hotkey::hotkey::HotKey>,
+    hotkey_status: String,
+    confirm_reset: bool, // "Reset to defaults" was clicked and awaits confirmation
 }
 
 impl Default for CaptureApp {
     fn default() -> Self {
-        let presets = crate::presets::get_all_presets().unwrap_or_default();
-        let mut preset_names: Vec<String> = presets.keys().cloned().collect();
-        preset_names.sort();
-
-        Self {
+        let mut app = Self {
             current_tab: Tab::Capture,
             config: CaptureConfig::default(),
             status: Arc::new(Mutex::new(CaptureStatus::Idle)),
             is_running: Arc::new(Mutex::new(false)),
             should_stop: Arc::new(Mutex::new(false)),
+            should_pause: Arc::new(Mutex::new(false)),
             logs: Arc::new(Mutex::new(Vec::new())),
-            presets,
-            preset_names,
+            presets: HashMap::new(),
+            preset_names: Vec::new(),
+            custom_presets: HashMap::new(),
+            custom_presets_dirty: false,
+            new_preset: NewPresetForm::default(),
+            preset_status: String::new(),
+            displays: crate::ScreenCapture::display_descriptions().unwrap_or_default(),
             font_status: "Using default font".to_string(),
-        }
+            font_generation: 0,
+            font_loading: false,
+            loaded_font: Arc::new(Mutex::new(None)),
+            picked_font_path: Arc::new(Mutex::new(None)),
+            cli_command: String::new(),
+            cli_copied_at: None,
+            last_result: Arc::new(Mutex::new(None)),
+            image_copied_at: None,
+            preview: Arc::new(Mutex::new(None)),
+            preview_texture: None,
+            hotkey_manager: None,
+            registered_hotkey: None,
+            hotkey_status: String::new(),
+            confirm_reset: false,
+        };
+        app.reload_presets();
+        app
     }
 }
 
@@ -124,7 +250,69 @@ impl CaptureApp {
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         // Load fonts to support Unicode (including Korean, Japanese, Chinese, etc.)
         Self::setup_fonts(&cc.egui_ctx);
-        Self::default()
+        let mut app = Self {
+            config: CaptureConfig::load(),
+            ..Self::default()
+        };
+
+        // Created here because macOS only allows it on the main thread
+        match global_hotkey::GlobalHotKeyManager::new() {
+            Ok(manager) => {
+                app.hotkey_manager = Some(manager);
+                app.apply_hotkey();
+            }
+            Err(e) => app.hotkey_status = format!("Global hotkeys unavailable: {}", e),
+        }
+        app
+    }
+
+    /// Registers `config.hotkey` in place of the previous hotkey
+    fn apply_hotkey(&mut self) {
+        let Some(manager) = &self.hotkey_manager else {
+            return;
+        };
+
+        if let Some(previous) = self.registered_hotkey.take() {
+            let _ = manager.unregister(previous);
+        }
+
+        let name = self.config.hotkey.trim();
+        if name.is_empty() {
+            self.hotkey_status = "Hotkey disabled".to_string();
+            return;
+        }
+
+        self.hotkey_status = match name.parse::<global_hotkey::hotkey::HotKey>() {
+            Ok(hotkey) => match manager.register(hotkey) {
+                Ok(()) => {
+                    self.registered_hotkey = Some(hotkey);
+                    format!("{} starts and stops a capture from any app", name)
+                }
+                Err(e) => format!("Could not register {}: {}", name, e),
+            },
+            Err(e) => format!("Invalid hotkey '{}': {}", name, e),
+        };
+    }
+
+    /// Starts a capture, or stops the running one, on each hotkey press
+    fn handle_hotkey(&mut self, ctx: &egui::Context) {
+        let Some(hotkey) = self.registered_hotkey else {
+            return;
+        };
+
+        while let Ok(event) = global_hotkey::GlobalHotKeyEvent::receiver().try_recv() {
+            if event.id != hotkey.id() || event.state != global_hotkey::HotKeyState::Pressed {
+                continue;
+            }
+            if *self.is_running.lock().unwrap() {
+                self.stop_capture();
+            } else {
+                self.start_capture();
+            }
+        }
+
+        // egui only repaints on its own input, which the target app has
+        ctx.request_repaint_after(std::time::Duration::from_millis(gui_const::HOTKEY_POLL_MS));
     }
 
     fn setup_fonts(ctx: &egui::Context) {
@@ -182,13 +370,52 @@ impl CaptureApp {
         ctx.set_fonts(fonts);
     }
 
-    fn load_font_from_path(&mut self, ctx: &egui::Context, path: &str) -> bool {
+    /// Reads the font on a background thread so a slow disk or network
+    /// mount doesn't freeze the window; `apply_loaded_font` installs it
+    fn load_font_from_path(&mut self, ctx: &egui::Context, path: &str) {
         if path.is_empty() {
             self.font_status = "No font path specified".to_string();
-            return false;
+            return;
+        }
+
+        self.font_generation += 1;
+        self.font_loading = true;
+        self.font_status = format!("Loading {}...", path);
+
+        let generation = self.font_generation;
+        let loaded_font = Arc::clone(&self.loaded_font);
+        let ctx = ctx.clone();
+        let path = path.to_string();
+        thread::spawn(move || {
+            let data = std::fs::read(&path);
+            *loaded_font.lock().unwrap() = Some(LoadedFont {
+                generation,
+                path,
+                data,
+            });
+            ctx.request_repaint();
+        });
+    }
+
+    /// Forgets the running font load; the reader thread finishes on its own
+    fn cancel_font_load(&mut self) {
+        self.font_generation += 1;
+        self.font_loading = false;
+        self.font_status = "Font loading cancelled".to_string();
+    }
+
+    /// Installs a font once its background read has finished
+    fn apply_loaded_font(&mut self, ctx: &egui::Context) {
+        let Some(loaded) = self.loaded_font.lock().unwrap().take() else {
+            return;
+        };
+        if loaded.generation != self.font_generation {
+            return;
         }
+        self.font_loading = false;
+        let path = loaded.path.as_str();
 
-        match std::fs::read(path) {
+        match loaded.data {
             Ok(font_data) => {
                 let mut fonts = egui::FontDefinitions::default();
 
@@ -218,15 +445,31 @@ impl CaptureApp {
                     .unwrap_or(path);
 
                 self.font_status = format!("Loaded: {}", filename);
-                true
             }
             Err(e) => {
                 self.font_status = format!("Failed to load font: {}", e);
-                false
             }
         }
     }
 
+    /// Opens the font picker without blocking the UI; the chosen path shows
+    /// up in the font field on a later frame
+    fn browse_font(&mut self, ctx: &egui::Context) {
+        let dialog = rfd::AsyncFileDialog::new()
+            .add_filter("Font files", &["ttf", "otf", "ttc"])
+            .pick_file();
+        let picked_font_path = Arc::clone(&self.picked_font_path);
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            if let Some(file) = pollster::block_on(dialog)
+                && let Some(path) = file.path().to_str()
+            {
+                *picked_font_path.lock().unwrap() = Some(path.to_string());
+                ctx.request_repaint();
+            }
+        });
+    }
+
     fn start_capture(&mut self) {
         // Validate output format before starting
         if let Err(e) = crate::validate_format(&self.config.output_format) {
@@ -235,27 +478,48 @@ impl CaptureApp {
         }
 
         let config = self.config.clone();
+        let dry_run = config.dry_run;
         let status = Arc::clone(&self.status);
         let is_running = Arc::clone(&self.is_running);
         let should_stop = Arc::clone(&self.should_stop);
+        let should_pause = Arc::clone(&self.should_pause);
         let logs = Arc::clone(&self.logs);
+        let preview = Arc::clone(&self.preview);
+        let last_result = Arc::clone(&self.last_result);
 
-        // Set running state and reset stop flag
+        // Set running state and reset stop and pause flags
         *is_running.lock().unwrap() = true;
         *should_stop.lock().unwrap() = false;
-        *status.lock().unwrap() = CaptureStatus::Running("Initializing capture...".to_string());
+        *should_pause.lock().unwrap() = false;
+        *status.lock().unwrap() = CaptureStatus::running("Initializing capture...".to_string());
 
-        // Clear previous logs
+        // Clear previous logs and preview
         logs.lock().unwrap().clear();
+        *preview.lock().unwrap() = None;
+        self.preview_texture = None;
+        *last_result.lock().unwrap() = None;
+        self.image_copied_at = None;
 
         // Spawn capture thread
         thread::spawn(move || {
-            let result =
-                Self::run_capture(config, status.clone(), should_stop.clone(), logs.clone());
+            let result = Self::run_capture(
+                config,
+                status.clone(),
+                should_stop.clone(),
+                should_pause,
+                logs.clone(),
+                preview,
+                last_result,
+            );
 
             *is_running.lock().unwrap() = false;
 
             match result {
+                Ok(_) if dry_run => {
+                    *status.lock().unwrap() = CaptureStatus::Completed(
+                        "Dry run finished, see the Capture Log (nothing saved)".to_string(),
+                    );
+                }
                 Ok(output_path) => {
                     *status.lock().unwrap() =
                         CaptureStatus::Completed(format!("Successfully saved to: {}", output_path));
@@ -272,6 +536,26 @@ impl CaptureApp {
         *self.should_stop.lock().unwrap() = true;
     }
 
+    fn toggle_pause(&mut self) {
+        let mut paused = self.should_pause.lock().unwrap();
+        *paused = !*paused;
+    }
+
+    /// Downscales a captured frame to fit the preview bounds
+    fn preview_image(frame: &image::RgbaImage) -> egui::ColorImage {
+        let scale = (gui_const::PREVIEW_MAX_WIDTH as f32 / frame.width() as f32)
+            .min(gui_const::PREVIEW_MAX_HEIGHT as f32 / frame.height() as f32)
+            .min(1.0);
+        let width = ((frame.width() as f32 * scale) as u32).max(1);
+        let height = ((frame.height() as f32 * scale) as u32).max(1);
+
+        let thumbnail = image::imageops::thumbnail(frame, width, height);
+        egui::ColorImage::from_rgba_unmultiplied(
+            [width as usize, height as usize],
+            thumbnail.as_raw(),
+        )
+    }
+
     fn log(logs: &Arc<Mutex<Vec<String>>>, message: String) {
         let timestamp = chrono::Local::now().format("%H:%M:%S%.6f");
         let log_entry = format!("[{}] {}", timestamp, message);
@@ -282,10 +566,26 @@ impl CaptureApp {
         config: CaptureConfig,
         status: Arc<Mutex<CaptureStatus>>,
         should_stop: Arc<Mutex<bool>>,
+        should_pause: Arc<Mutex<bool>>,
         logs: Arc<Mutex<Vec<String>>>,
+        preview: Arc<Mutex<Option<egui::ColorImage>>>,
+        last_result: Arc<Mutex<Option<image::RgbaImage>>>,
     ) -> anyhow::Result<String> {
         use crate::ScreenCapture;
 
+        // Fail before the countdown rather than partway through the capture
+        *status.lock().unwrap() = CaptureStatus::running("Checking permissions...".to_string());
+        let permissions = ScreenCapture::new()
+            .with_display(config.display)
+            .check_permissions(true);
+        if !permissions.all_granted() {
+            Self::log(&logs, format!("Permission check failed:\n{}", permissions));
+            return Err(anyhow::anyhow!(
+                "missing permissions. {}",
+                permissions.remediation.join(" ")
+            ));
+        }
+
         // Countdown display
         if config.delay > 0 {
             Self::log(
@@ -293,11 +593,19 @@ impl CaptureApp {
                 format!("Starting capture in {} seconds...", config.delay),
             );
 
-            for remaining in (1..=config.delay).rev() {
-                *status.lock().unwrap() = CaptureStatus::Running(format!(
-                    "Starting in {} second{}...",
-                    remaining,
-                    if remaining > 1 { "s" } else { "" }
+            let tick = std::time::Duration::from_millis(gui_const::COUNTDOWN_TICK_MS);
+            let deadline = std::time::Instant::now() + std::time::Duration::from_secs(config.delay);
+
+            // Short ticks so Stop takes effect right away
+            loop {
+                let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+                if remaining.is_zero() {
+                    break;
+                }
+
+                *status.lock().unwrap() = CaptureStatus::running(format!(
+                    "Starting in {:.1} seconds...",
+                    remaining.as_secs_f32()
                 ));
 
                 // Check if stop was requested during countdown
@@ -305,18 +613,47 @@ impl CaptureApp {
                     return Err(anyhow::anyhow!("Capture cancelled during countdown"));
                 }
 
-                std::thread::sleep(std::time::Duration::from_secs(1));
+                std::thread::sleep(tick.min(remaining));
             }
         }
 
-        let capture = ScreenCapture::new();
+        let capture = ScreenCapture::new()
+            .with_display(config.display)
+            .with_grayscale(config.grayscale)
+            .with_pixel_tolerance(config.pixel_tolerance)
+            .with_diff_threshold(
+                config
+                    .diff_threshold_enabled
+                    .then_some(config.diff_threshold),
+            )
+            .with_min_scrolls(config.min_scrolls)
+            .with_auto_overlap(config.auto_overlap)
+            .with_overlap_percent(config.overlap_in_percent.then_some(config.overlap_percent))
+            .with_dry_run(config.dry_run)
+            .with_pause_flag(should_pause)
+            .with_progress_observer({
+                let status = Arc::clone(&status);
+                move |fraction| {
+                    if let CaptureStatus::Running { progress, .. } = &mut *status.lock().unwrap() {
+                        *progress = Some(fraction);
+                    }
+                }
+            })
+            .with_dump_frames(
+                Some(config.dump_frames.trim().to_string()).filter(|dir| !dir.is_empty()),
+            )
+            .with_frame_observer(move |frame| {
+                *preview.lock().unwrap() = Some(Self::preview_image(frame));
+            });
 
         // Prepare crop option
         let crop_option = if config.use_preset && !config.selected_preset.is_empty() {
             // Use preset value directly
             use crate::presets;
             if let Ok(all_presets) = presets::get_all_presets() {
-                all_presets.get(&config.selected_preset).cloned()
+                all_presets
+                    .get(&config.selected_preset)
+                    .map(|preset| preset.crop.clone())
             } else {
                 None
             }
@@ -329,52 +666,169 @@ impl CaptureApp {
             None
         };
 
-        Self::log(&logs, "Starting screenshot mode...".to_string());
-        *status.lock().unwrap() = CaptureStatus::Running("Capturing screenshots...".to_string());
-
         let max_scrolls = if config.max_scrolls.is_empty() {
             None
         } else {
             config.max_scrolls.parse().ok()
         };
 
+        Self::log(&logs, "Starting screenshot mode...".to_string());
+        *status.lock().unwrap() = CaptureStatus::Running {
+            msg: "Capturing screenshots...".to_string(),
+            // The bar shows from the start when the scroll count is known
+            progress: max_scrolls.map(|_: usize| 0.0),
+        };
+
         Self::log(
             &logs,
             format!(
-                "Max scrolls: {:?}, Scroll delay: {}ms, Overlap: {}px",
+                "Max scrolls: {:?}, Scroll delay: {}ms, Overlap: {}",
                 max_scrolls
                     .map(|n: usize| n.to_string())
                     .unwrap_or("unlimited".to_string()),
                 config.scroll_delay,
-                config.overlap
+                if config.auto_overlap {
+                    "auto".to_string()
+                } else if config.overlap_in_percent {
+                    format!("{}%", config.overlap_percent)
+                } else {
+                    format!("{}px", config.overlap)
+                }
             ),
         );
 
-        let result_image = capture.capture_with_scroll_with_stop(
-            config.overlap,
-            max_scrolls,
-            0, // Delay already handled in GUI countdown
-            config.scroll_key.as_str(),
-            config.window_only,
-            crop_option,
-            config.scroll_delay,
-            should_stop.clone(),
-            logs.clone(),
-        )?;
-
-        Self::log(&logs, "Saving image...".to_string());
+        let options = CaptureOptions::new()
+            .with_overlap(config.overlap)
+            .with_max_scrolls(max_scrolls)
+            .with_delay(0) // Delay already handled in GUI countdown
+            .with_key(config.scroll_key.into())
+            .with_window_only(config.window_only)
+            .with_crop(crop_option)
+            .with_scroll_delay(config.scroll_delay);
+        let result_image =
+            capture.capture_with_scroll_with_stop(&options, should_stop.clone(), logs.clone())?;
+
+        if config.dry_run {
+            return Ok(crate::build_output_path(
+                &config.output_filename,
+                &config.output_format,
+            ));
+        }
 
-        *status.lock().unwrap() = CaptureStatus::Running("Saving image...".to_string());
+        Self::save_result(&config, result_image, &status, &logs, &last_result)
+    }
 
+    /// Writes the stitched image with the configured output settings and
+    /// keeps it for "Copy to clipboard". Returns the path it was saved to.
+    fn save_result(
+        config: &CaptureConfig,
+        result_image: image::RgbaImage,
+        status: &Arc<Mutex<CaptureStatus>>,
+        logs: &Arc<Mutex<Vec<String>>>,
+        last_result: &Arc<Mutex<Option<image::RgbaImage>>>,
+    ) -> anyhow::Result<String> {
         // Build full output path with format
         let output_path = crate::build_output_path(&config.output_filename, &config.output_format);
-        result_image.save(&output_path)?;
+
+        let output_path = if config.auto_name {
+            crate::next_available_path(std::path::Path::new(&output_path))
+                .to_string_lossy()
+                .into_owned()
+        } else {
+            output_path
+        };
+
+        Self::log(logs, "Saving image...".to_string());
+
+        *status.lock().unwrap() = CaptureStatus::running("Saving image...".to_string());
+
+        let result_image = if config.scale != 1.0 {
+            let scaled = crate::scale_image(&result_image, config.scale);
+            Self::log(
+                logs,
+                format!(
+                    "Scaled by {}: {}x{} -> {}x{}",
+                    config.scale,
+                    result_image.width(),
+                    result_image.height(),
+                    scaled.width(),
+                    scaled.height()
+                ),
+            );
+            scaled
+        } else {
+            result_image
+        };
+
+        let threshold = config.bilevel.then_some(config.threshold);
+        let result_image = if config.grayscale || threshold.is_some() {
+            let gray = crate::to_luma(&result_image, threshold);
+            let toned = image::DynamicImage::ImageLuma8(gray.clone()).to_rgba8();
+            if crate::is_lossy_format(&config.output_format) {
+                crate::save_image(&toned, &output_path, config.quality)?;
+            } else {
+                gray.save(&output_path)?;
+            }
+            toned
+        } else {
+            crate::save_image(&result_image, &output_path, config.quality)?;
+            result_image
+        };
+        *last_result.lock().unwrap() = Some(result_image);
         Ok(output_path)
     }
+
+    /// Stitches the frames in `dir` on a background thread and saves the
+    /// result like a capture
+    fn start_stitch(&mut self, dir: std::path::PathBuf) {
+        if let Err(e) = crate::validate_format(&self.config.output_format) {
+            *self.status.lock().unwrap() = CaptureStatus::Error(format!("{}", e));
+            return;
+        }
+
+        let config = self.config.clone();
+        let status = Arc::clone(&self.status);
+        let is_running = Arc::clone(&self.is_running);
+        let logs = Arc::clone(&self.logs);
+        let last_result = Arc::clone(&self.last_result);
+
+        *is_running.lock().unwrap() = true;
+        *status.lock().unwrap() = CaptureStatus::running("Stitching frames...".to_string());
+        logs.lock().unwrap().clear();
+        *self.preview.lock().unwrap() = None;
+        self.preview_texture = None;
+        *last_result.lock().unwrap() = None;
+        self.image_copied_at = None;
+
+        thread::spawn(move || {
+            let capture = crate::ScreenCapture::new()
+                .with_grayscale(config.grayscale)
+                .with_overlap_percent(config.overlap_in_percent.then_some(config.overlap_percent));
+            let result = capture
+                .stitch_directory_with_logs(&dir, config.overlap, logs.clone())
+                .map_err(anyhow::Error::from)
+                .and_then(|image| Self::save_result(&config, image, &status, &logs, &last_result));
+
+            *is_running.lock().unwrap() = false;
+
+            *status.lock().unwrap() = match result {
+                Ok(output_path) => {
+                    CaptureStatus::Completed(format!("Successfully saved to: {}", output_path))
+                }
+                Err(e) => CaptureStatus::Error(format!("Stitching failed: {}", e)),
+            };
+        });
+    }
 }
 
 impl eframe::App for CaptureApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.handle_hotkey(ctx);
+        self.apply_loaded_font(ctx);
+        if let Some(path) = self.picked_font_path.lock().unwrap().take() {
+            self.config.font_path = path;
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             // Tab buttons at the top
             ui.horizontal(|ui| {
@@ -392,9 +846,53 @@ impl eframe::App for CaptureApp {
             });
         });
     }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        // Best effort: a failed save just means defaults next launch
+        if let Err(e) = self.config.save() {
+            eprintln!("Failed to save GUI settings: {}", e);
+        }
+    }
 }
 
 impl CaptureApp {
+    /// Restores the default capture settings, clearing the crop and the
+    /// selected preset. The Settings tab's font, hotkey and colors are kept.
+    fn reset_config(&mut self) {
+        self.config = CaptureConfig {
+            font_path: std::mem::take(&mut self.config.font_path),
+            hotkey: std::mem::take(&mut self.config.hotkey),
+            status_color: self.config.status_color,
+            ..CaptureConfig::default()
+        };
+        Self::log(&self.logs, "Capture settings reset to defaults".to_string());
+    }
+
+    /// "Copy to clipboard" for the last stitched image, if there is one
+    fn render_copy_image_button(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let last_result = self.last_result.lock().unwrap();
+        let Some(image) = last_result.as_ref() else {
+            return;
+        };
+
+        if ui.button("📋 Copy to clipboard").clicked() {
+            match crate::copy_to_clipboard(image, false) {
+                Ok(()) => self.image_copied_at = Some(std::time::Instant::now()),
+                Err(e) => Self::log(&self.logs, format!("{}", e)),
+            }
+        }
+
+        if let Some(copied_at) = self.image_copied_at {
+            let feedback = std::time::Duration::from_millis(gui_const::COPY_FEEDBACK_MS);
+            if copied_at.elapsed() < feedback {
+                ui.colored_label(egui::Color32::GREEN, "Copied!");
+                ctx.request_repaint_after(feedback - copied_at.elapsed());
+            } else {
+                self.image_copied_at = None;
+            }
+        }
+    }
+
     fn render_capture_tab(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
         // Configuration UI
         ui.heading("Screenshot Mode");
@@ -406,17 +904,23 @@ impl CaptureApp {
             CaptureStatus::Idle => {
                 ui.label("Ready to capture");
             }
-            CaptureStatus::Running(msg) => {
+            CaptureStatus::Running { msg, progress } => {
                 let color = egui::Color32::from_rgb(
                     self.config.status_color[0],
                     self.config.status_color[1],
                     self.config.status_color[2],
                 );
                 ui.colored_label(color, format!("⏳ {}", msg));
+                if let Some(progress) = progress {
+                    ui.add(egui::ProgressBar::new(*progress).show_percentage());
+                }
                 ctx.request_repaint(); // Keep updating while running
             }
             CaptureStatus::Completed(msg) => {
-                ui.colored_label(egui::Color32::GREEN, format!("✓ {}", msg));
+                ui.horizontal(|ui| {
+                    ui.colored_label(egui::Color32::GREEN, format!("✓ {}", msg));
+                    self.render_copy_image_button(ui, ctx);
+                });
             }
             CaptureStatus::Error(msg) => {
                 ui.colored_label(egui::Color32::RED, format!("✗ {}", msg));
@@ -441,6 +945,44 @@ impl CaptureApp {
             {
                 self.stop_capture();
             }
+
+            let is_paused = *self.should_pause.lock().unwrap();
+            let pause_label = if is_paused { "▶ Resume" } else { "⏸ Pause" };
+            if ui
+                .add_enabled(is_running, egui::Button::new(pause_label))
+                .clicked()
+            {
+                self.toggle_pause();
+            }
+
+            if ui
+                .add_enabled(
+                    !is_running,
+                    egui::Button::new("🧩 Stitch existing frames..."),
+                )
+                .on_hover_text("Pick a folder of PNG frames to stitch in file name order")
+                .clicked()
+                && let Some(dir) = rfd::FileDialog::new().pick_folder()
+            {
+                self.start_stitch(dir);
+            }
+
+            if self.confirm_reset {
+                ui.label("Reset all capture settings?");
+                if ui.button("Reset").clicked() {
+                    self.reset_config();
+                    self.confirm_reset = false;
+                }
+                if ui.button("Cancel").clicked() {
+                    self.confirm_reset = false;
+                }
+            } else if ui
+                .add_enabled(!is_running, egui::Button::new("↺ Reset to defaults"))
+                .on_hover_text("Restore default capture settings, crop and preset included")
+                .clicked()
+            {
+                self.confirm_reset = true;
+            }
         });
 
         ui.add_space(20.0);
@@ -474,12 +1016,70 @@ impl CaptureApp {
                     });
             });
 
+            ui.checkbox(
+                &mut self.config.auto_name,
+                "Don't overwrite: save as 'name (1)', 'name (2)', ... if the file exists",
+            );
+
+            ui.horizontal(|ui| {
+                let lossy = crate::is_lossy_format(&self.config.output_format);
+                ui.add_enabled(lossy, egui::Label::new("Quality (jpg/webp):"));
+                ui.add_enabled(
+                    lossy,
+                    egui::Slider::new(
+                        &mut self.config.quality,
+                        gui_const::QUALITY_MIN..=gui_const::QUALITY_MAX,
+                    ),
+                );
+            });
+
             ui.horizontal(|ui| {
-                ui.label("Overlap pixels:");
-                ui.add(egui::Slider::new(
-                    &mut self.config.overlap,
-                    gui_const::OVERLAP_MIN..=gui_const::OVERLAP_MAX,
-                ));
+                ui.label("Scale:");
+                ui.add(
+                    egui::Slider::new(
+                        &mut self.config.scale,
+                        gui_const::SCALE_MIN..=gui_const::SCALE_MAX,
+                    )
+                    .suffix("x"),
+                );
+                if self.config.scale > 1.0 {
+                    ui.colored_label(egui::Color32::YELLOW, "⚠ Enlarging adds no detail");
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.config.grayscale, "Grayscale");
+                ui.checkbox(&mut self.config.bilevel, "Black & white, cutoff:");
+                ui.add_enabled(
+                    self.config.bilevel,
+                    egui::Slider::new(&mut self.config.threshold, 0..=255),
+                );
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Overlap:");
+                if self.config.overlap_in_percent {
+                    ui.add_enabled(
+                        !self.config.auto_overlap,
+                        egui::Slider::new(
+                            &mut self.config.overlap_percent,
+                            gui_const::OVERLAP_PERCENT_MIN..=gui_const::OVERLAP_PERCENT_MAX,
+                        )
+                        .suffix("%"),
+                    );
+                } else {
+                    ui.add_enabled(
+                        !self.config.auto_overlap,
+                        egui::Slider::new(
+                            &mut self.config.overlap,
+                            gui_const::OVERLAP_MIN..=gui_const::OVERLAP_MAX,
+                        )
+                        .suffix(" px"),
+                    );
+                }
+                ui.checkbox(&mut self.config.overlap_in_percent, "% of frame")
+                    .on_hover_text("Scale the overlap with the captured frame height");
+                ui.checkbox(&mut self.config.auto_overlap, "Auto");
             });
 
             ui.horizontal(|ui| {
@@ -499,6 +1099,7 @@ impl CaptureApp {
                     ScrollKey::PageDown,
                     "Page Down",
                 );
+                ui.radio_value(&mut self.config.scroll_key, ScrollKey::Wheel, "Mouse Wheel");
             });
         });
 
@@ -516,6 +1117,11 @@ impl CaptureApp {
                 );
             });
 
+            ui.horizontal(|ui| {
+                ui.label("Min scrolls before end detection:");
+                ui.add(egui::DragValue::new(&mut self.config.min_scrolls));
+            });
+
             ui.horizontal(|ui| {
                 ui.label("Scroll delay (milliseconds):");
                 ui.add(egui::Slider::new(
@@ -523,6 +1129,42 @@ impl CaptureApp {
                     gui_const::SCROLL_DELAY_MIN..=gui_const::SCROLL_DELAY_MAX,
                 ));
             });
+
+            ui.horizontal(|ui| {
+                ui.label("Pixel tolerance (0 = exact):");
+                ui.add(egui::Slider::new(
+                    &mut self.config.pixel_tolerance,
+                    gui_const::PIXEL_TOLERANCE_MIN..=gui_const::PIXEL_TOLERANCE_MAX,
+                ));
+            });
+
+            ui.horizontal(|ui| {
+                ui.checkbox(
+                    &mut self.config.diff_threshold_enabled,
+                    "Diff threshold (%):",
+                )
+                .on_hover_text("Higher tolerates more motion before frames count as different");
+                ui.add_enabled(
+                    self.config.diff_threshold_enabled,
+                    egui::Slider::new(
+                        &mut self.config.diff_threshold,
+                        gui_const::DIFF_THRESHOLD_MIN..=gui_const::DIFF_THRESHOLD_MAX,
+                    ),
+                );
+            });
+
+            ui.checkbox(
+                &mut self.config.dry_run,
+                "Dry run (count scrolls and log stats, don't save)",
+            );
+
+            ui.horizontal(|ui| {
+                ui.label("Dump frames to (leave empty to disable):");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.config.dump_frames)
+                        .desired_width(ui.available_width()),
+                );
+            });
         });
 
         ui.add_space(10.0);
@@ -531,6 +1173,22 @@ impl CaptureApp {
         ui.group(|ui| {
             ui.label("Crop Settings");
 
+            ui.horizontal(|ui| {
+                ui.label("Display:");
+                let selected = self
+                    .displays
+                    .get(self.config.display)
+                    .cloned()
+                    .unwrap_or_else(|| format!("{}: not connected", self.config.display));
+                egui::ComboBox::from_id_salt("display_selector")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        for (index, description) in self.displays.iter().enumerate() {
+                            ui.selectable_value(&mut self.config.display, index, description);
+                        }
+                    });
+            });
+
             ui.checkbox(&mut self.config.window_only, "Capture focused window only");
 
             ui.separator();
@@ -563,16 +1221,31 @@ impl CaptureApp {
                                     )
                                     .clicked()
                                 {
-                                    // Apply preset values to crop fields
-                                    if let Some(crop_str) = self.presets.get(preset_name) {
+                                    // Apply preset values to crop and scroll fields
+                                    if let Some(preset) = self.presets.get(preset_name) {
                                         if let Some((x, y, w, h)) =
-                                            crate::presets::parse_crop_region(crop_str)
+                                            crate::presets::parse_crop_region(&preset.crop)
                                         {
                                             self.config.crop_x = x;
                                             self.config.crop_y = y;
                                             self.config.crop_width = w;
                                             self.config.crop_height = h;
                                         }
+                                        if let Some(overlap) = preset.overlap {
+                                            self.config.overlap = overlap;
+                                            self.config.auto_overlap = false;
+                                            self.config.overlap_in_percent = false;
+                                        }
+                                        if let Some(scroll_key) = preset
+                                            .key
+                                            .as_deref()
+                                            .and_then(|key| key.parse::<ScrollKey>().ok())
+                                        {
+                                            self.config.scroll_key = scroll_key;
+                                        }
+                                        if let Some(scroll_delay) = preset.scroll_delay {
+                                            self.config.scroll_delay = scroll_delay;
+                                        }
                                     }
                                 }
                             }
@@ -580,8 +1253,8 @@ impl CaptureApp {
                 });
 
                 if !self.config.selected_preset.is_empty() {
-                    if let Some(value) = self.presets.get(&self.config.selected_preset) {
-                        ui.label(format!("Region: {}", value));
+                    if let Some(preset) = self.presets.get(&self.config.selected_preset) {
+                        ui.label(format!("Region: {}", preset));
                     }
                 }
             }
@@ -609,21 +1282,32 @@ impl CaptureApp {
 
         ui.add_space(20.0);
 
-        // Show equivalent CLI command
+        // Show equivalent CLI command (generated once so display and copy always match)
+        self.cli_command = self.generate_cli_command();
+
         ui.group(|ui| {
             ui.horizontal(|ui| {
                 ui.label("Equivalent CLI command:");
                 if ui.button("📋 Copy").clicked() {
-                    let cmd = self.generate_cli_command();
-                    ui.ctx().copy_text(cmd);
+                    ctx.copy_text(self.cli_command.clone());
+                    self.cli_copied_at = Some(std::time::Instant::now());
+                }
+
+                if let Some(copied_at) = self.cli_copied_at {
+                    let feedback = std::time::Duration::from_millis(gui_const::COPY_FEEDBACK_MS);
+                    if copied_at.elapsed() < feedback {
+                        ui.colored_label(egui::Color32::GREEN, "Copied!");
+                        ctx.request_repaint_after(feedback - copied_at.elapsed());
+                    } else {
+                        self.cli_copied_at = None;
+                    }
                 }
             });
 
             ui.add_space(5.0);
-            let cmd = self.generate_cli_command();
 
             ui.add(
-                egui::TextEdit::multiline(&mut cmd.as_str())
+                egui::TextEdit::multiline(&mut self.cli_command.as_str())
                     .code_editor()
                     .desired_width(f32::INFINITY),
             );
@@ -638,8 +1322,14 @@ impl CaptureApp {
 
             let logs = self.logs.lock().unwrap();
 
-            // Use remaining available height, with a reasonable minimum
-            let available_height = ui.available_height();
+            // Use remaining available height (minus room for the preview),
+            // with a reasonable minimum
+            let preview_room = if self.preview_texture.is_some() {
+                gui_const::PREVIEW_MAX_HEIGHT as f32 + 30.0
+            } else {
+                0.0
+            };
+            let available_height = ui.available_height() - preview_room;
             let scroll_height = available_height.max(200.0);
 
             egui::ScrollArea::vertical()
@@ -656,6 +1346,28 @@ impl CaptureApp {
                     }
                 });
         });
+
+        // Live preview of the most recent frame
+        if let Some(frame) = self.preview.lock().unwrap().take() {
+            match &mut self.preview_texture {
+                Some(texture) => texture.set(frame, egui::TextureOptions::LINEAR),
+                None => {
+                    self.preview_texture = Some(ctx.load_texture(
+                        "capture_preview",
+                        frame,
+                        egui::TextureOptions::LINEAR,
+                    ));
+                }
+            }
+        }
+
+        if let Some(texture) = &self.preview_texture {
+            ui.add_space(10.0);
+            ui.group(|ui| {
+                ui.label("Latest Frame");
+                ui.add(egui::Image::new(texture).fit_to_original_size(1.0));
+            });
+        }
     }
 
     fn render_settings_tab(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
@@ -672,19 +1384,17 @@ impl CaptureApp {
                     .desired_width(ui.available_width() - 80.0));
 
                 if ui.button("Browse...").clicked() {
-                    if let Some(path) = rfd::FileDialog::new()
-                        .add_filter("Font files", &["ttf", "otf", "ttc"])
-                        .pick_file()
-                    {
-                        if let Some(path_str) = path.to_str() {
-                            self.config.font_path = path_str.to_string();
-                        }
-                    }
+                    self.browse_font(ctx);
                 }
             });
 
             ui.horizontal(|ui| {
-                if ui.button("Load Font").clicked() {
+                if self.font_loading {
+                    ui.spinner();
+                    if ui.button("Cancel").clicked() {
+                        self.cancel_font_load();
+                    }
+                } else if ui.button("Load Font").clicked() {
                     let font_path = self.config.font_path.clone();
                     self.load_font_from_path(ctx, &font_path);
                 }
@@ -697,6 +1407,28 @@ impl CaptureApp {
 
         ui.add_space(10.0);
 
+        // Global hotkey
+        ui.group(|ui| {
+            ui.label("Global Hotkey");
+
+            ui.horizontal(|ui| {
+                ui.label("Start/stop:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.config.hotkey)
+                        .hint_text("e.g. F9 or ctrl+shift+KeyS, empty to disable")
+                        .desired_width(200.0),
+                );
+
+                if ui.button("Apply").clicked() {
+                    self.apply_hotkey();
+                }
+            });
+
+            ui.label(&self.hotkey_status);
+        });
+
+        ui.add_space(10.0);
+
         // UI settings
         ui.group(|ui| {
             ui.label("UI Settings");
@@ -759,6 +1491,128 @@ impl CaptureApp {
                 }
             });
         });
+
+        ui.add_space(10.0);
+
+        self.render_preset_manager(ui);
+    }
+
+    /// Lists built-in and custom presets, with an add form and delete buttons
+    /// for custom ones. Edits stay local until Save writes the presets file.
+    fn render_preset_manager(&mut self, ui: &mut egui::Ui) {
+        let builtin = crate::presets::get_builtin_presets();
+
+        ui.group(|ui| {
+            ui.label("Presets");
+
+            let mut names: Vec<&String> =
+                builtin.keys().chain(self.custom_presets.keys()).collect();
+            names.sort();
+            names.dedup();
+
+            let mut to_delete = None;
+            egui::Grid::new("preset_manager")
+                .striped(true)
+                .show(ui, |ui| {
+                    for name in names {
+                        ui.label(name);
+                        match self.custom_presets.get(name) {
+                            Some(preset) => {
+                                ui.label(preset.to_string());
+                                ui.label(if builtin.contains_key(name) {
+                                    "custom (overrides built-in)"
+                                } else {
+                                    "custom"
+                                });
+                                if ui.button("Delete").clicked() {
+                                    to_delete = Some(name.clone());
+                                }
+                            }
+                            None => {
+                                ui.label(builtin[name].to_string());
+                                ui.label("built-in");
+                                ui.add_enabled(false, egui::Button::new("Delete"))
+                                    .on_disabled_hover_text("Built-in presets can't be deleted");
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+
+            if let Some(name) = to_delete {
+                self.custom_presets.remove(&name);
+                self.custom_presets_dirty = true;
+                self.preset_status = format!("Deleted '{}' (not saved yet)", name);
+            }
+
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                ui.label("Name:");
+                ui.add(egui::TextEdit::singleline(&mut self.new_preset.name).desired_width(120.0));
+                ui.label("X:");
+                ui.add(egui::DragValue::new(&mut self.new_preset.x));
+                ui.label("Y:");
+                ui.add(egui::DragValue::new(&mut self.new_preset.y));
+                ui.label("W:");
+                ui.add(egui::DragValue::new(&mut self.new_preset.width).range(1..=i32::MAX));
+                ui.label("H:");
+                ui.add(egui::DragValue::new(&mut self.new_preset.height).range(1..=i32::MAX));
+
+                if ui.button("Add").clicked() {
+                    let name = self.new_preset.name.trim().to_string();
+                    if name.is_empty() || name.contains(':') {
+                        self.preset_status =
+                            "Preset name must be non-empty and without ':'".to_string();
+                    } else {
+                        let crop = format!(
+                            "{},{},{},{}",
+                            self.new_preset.x,
+                            self.new_preset.y,
+                            self.new_preset.width,
+                            self.new_preset.height
+                        );
+                        self.custom_presets
+                            .insert(name.clone(), crate::presets::Preset::from_crop(&crop));
+                        self.custom_presets_dirty = true;
+                        self.preset_status = format!("Added '{}' (not saved yet)", name);
+                        self.new_preset.name.clear();
+                    }
+                }
+            });
+
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(self.custom_presets_dirty, egui::Button::new("Save"))
+                    .clicked()
+                {
+                    match crate::presets::save_presets(&self.custom_presets) {
+                        Ok(()) => {
+                            self.reload_presets();
+                            self.preset_status = "Presets saved".to_string();
+                        }
+                        Err(e) => self.preset_status = format!("Failed to save presets: {}", e),
+                    }
+                }
+                if ui
+                    .add_enabled(self.custom_presets_dirty, egui::Button::new("Discard"))
+                    .clicked()
+                {
+                    self.reload_presets();
+                    self.preset_status = "Changes discarded".to_string();
+                }
+                ui.label(&self.preset_status);
+            });
+        });
+    }
+
+    /// Re-reads the presets file so the Capture tab dropdown and the Settings
+    /// list reflect what is on disk
+    fn reload_presets(&mut self) {
+        self.presets = crate::presets::get_all_presets().unwrap_or_default();
+        self.preset_names = self.presets.keys().cloned().collect();
+        self.preset_names.sort();
+        self.custom_presets = crate::presets::load_presets().unwrap_or_default();
+        self.custom_presets_dirty = false;
     }
 }
 
@@ -768,15 +1622,63 @@ impl CaptureApp {
 
         cmd.push(format!("--output {}", self.config.output_filename));
         cmd.push(format!("--format {}", self.config.output_format));
-        cmd.push(format!("--overlap {}", self.config.overlap));
+        if self.config.auto_name {
+            cmd.push("--auto-name".to_string());
+        }
+        if crate::is_lossy_format(&self.config.output_format)
+            && self.config.quality != defaults::QUALITY
+        {
+            cmd.push(format!("--quality {}", self.config.quality));
+        }
+        if self.config.scale != defaults::SCALE {
+            cmd.push(format!("--scale {}", self.config.scale));
+        }
+        if self.config.grayscale {
+            cmd.push("--grayscale".to_string());
+        }
+        if self.config.bilevel {
+            cmd.push(format!("--threshold {}", self.config.threshold));
+        }
+        if self.config.auto_overlap {
+            cmd.push("--overlap auto".to_string());
+        } else {
+            if self.config.overlap_in_percent {
+                cmd.push(format!("--overlap {}%", self.config.overlap_percent));
+            } else {
+                cmd.push(format!("--overlap {}", self.config.overlap));
+            }
+        }
         cmd.push(format!("--delay {}", self.config.delay));
         cmd.push(format!("--key {}", self.config.scroll_key.as_str()));
 
         if !self.config.max_scrolls.is_empty() {
             cmd.push(format!("--max-scrolls {}", self.config.max_scrolls));
         }
+        if self.config.min_scrolls > 0 {
+            cmd.push(format!("--min-scrolls {}", self.config.min_scrolls));
+        }
         cmd.push(format!("--scroll-delay {}", self.config.scroll_delay));
 
+        if self.config.pixel_tolerance > 0 {
+            cmd.push(format!("--pixel-tolerance {}", self.config.pixel_tolerance));
+        }
+
+        if self.config.diff_threshold_enabled {
+            cmd.push(format!("--diff-threshold {}", self.config.diff_threshold));
+        }
+
+        if self.config.dry_run {
+            cmd.push("--dry-run".to_string());
+        }
+
+        if !self.config.dump_frames.trim().is_empty() {
+            cmd.push(format!("--dump-frames {}", self.config.dump_frames.trim()));
+        }
+
+        if self.config.display > 0 {
+            cmd.push(format!("--display {}", self.config.display));
+        }
+
         if self.config.window_only {
             cmd.push("--window-only".to_string());
         }
@@ -798,6 +1700,7 @@ impl CaptureApp {
 }
 
 pub fn run_gui() -> Result<(), eframe::Error> {
+    crate::enable_dpi_awareness();
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([gui_const::WINDOW_WIDTH, gui_const::WINDOW_HEIGHT])
@@ -811,3 +1714,184 @@ pub fn run_gui() -> Result<(), eframe::Error> {
         Box::new(|cc| Ok(Box::new(CaptureApp::new(cc)))),
     )
 }
+
+/// The region picked in the overlay, handed back once its window closes
+type OverlayResult = Arc<Mutex<Option<(i32, i32, i32, i32)>>>;
+
+/// Fullscreen translucent window where a crop region is picked by dragging
+struct RegionOverlay {
+    drag_start: Option<egui::Pos2>,
+    selection: Option<egui::Rect>,
+    // Window (x, y, width, height) in crop coordinates whose edges the
+    // corners snap to
+    snap_window: Option<(i32, i32, i32, i32)>,
+    result: OverlayResult,
+}
+
+impl RegionOverlay {
+    /// Crop coordinate units per overlay point, and the overlay's origin
+    fn screen_transform(ctx: &egui::Context) -> (f32, egui::Pos2) {
+        let pixels_per_point = if cfg!(target_os = "macos") {
+            1.0
+        } else {
+            ctx.pixels_per_point()
+        };
+        let origin = ctx
+            .input(|i| i.viewport().outer_rect)
+            .map(|outer| outer.min)
+            .unwrap_or(egui::Pos2::ZERO);
+        (pixels_per_point, origin)
+    }
+
+    /// Moves a corner near the snap window's edges onto them
+    fn snap(&self, ctx: &egui::Context, pos: egui::Pos2) -> egui::Pos2 {
+        let Some(window) = self.snap_window else {
+            return pos;
+        };
+        let (pixels_per_point, origin) = Self::screen_transform(ctx);
+        let corner = (
+            ((origin.x + pos.x) * pixels_per_point).round() as i32,
+            ((origin.y + pos.y) * pixels_per_point).round() as i32,
+        );
+        let threshold = (display::SNAP_DISTANCE as f32 * pixels_per_point).round() as i32;
+        match crate::snap_to_window_edges(corner, window, threshold) {
+            ((x, y), true) => egui::pos2(
+                x as f32 / pixels_per_point - origin.x,
+                y as f32 / pixels_per_point - origin.y,
+            ),
+            (_, false) => pos,
+        }
+    }
+
+    /// Converts a rect in overlay points to crop coordinates: points on macOS
+    /// (scaled to pixels at capture time), screen pixels elsewhere
+    fn to_screen_pixels(ctx: &egui::Context, rect: egui::Rect) -> (i32, i32, i32, i32) {
+        let (pixels_per_point, origin) = Self::screen_transform(ctx);
+
+        (
+            ((origin.x + rect.min.x) * pixels_per_point).round() as i32,
+            ((origin.y + rect.min.y) * pixels_per_point).round() as i32,
+            (rect.width() * pixels_per_point).round() as i32,
+            (rect.height() * pixels_per_point).round() as i32,
+        )
+    }
+}
+
+impl eframe::App for RegionOverlay {
+    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+        [0.0, 0.0, 0.0, 0.0] // Fully transparent; the panel adds the dimming
+    }
+
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            return;
+        }
+
+        let dim = egui::Color32::from_rgba_unmultiplied(0, 0, 0, gui_const::OVERLAY_DIM_ALPHA);
+        egui::CentralPanel::default()
+            .frame(egui::Frame::NONE.fill(dim))
+            .show(ctx, |ui| {
+                let response = ui.allocate_response(ui.available_size(), egui::Sense::drag());
+
+                if response.drag_started() {
+                    self.drag_start = response
+                        .interact_pointer_pos()
+                        .map(|pos| self.snap(ctx, pos));
+                }
+                if let (Some(start), Some(current)) =
+                    (self.drag_start, response.interact_pointer_pos())
+                {
+                    let current = self.snap(ctx, current);
+                    self.selection = Some(egui::Rect::from_two_pos(start, current));
+                }
+
+                let painter = ui.painter();
+                painter.text(
+                    ui.max_rect().center_top() + egui::vec2(0.0, 40.0),
+                    egui::Align2::CENTER_CENTER,
+                    "Drag to select the capture region (Esc to cancel)",
+                    egui::FontId::proportional(20.0),
+                    egui::Color32::WHITE,
+                );
+
+                if let Some(rect) = self.selection {
+                    painter.rect_filled(rect, 0.0, egui::Color32::from_white_alpha(30));
+                    painter.rect_stroke(
+                        rect,
+                        0.0,
+                        egui::Stroke::new(2.0, egui::Color32::RED),
+                        egui::StrokeKind::Outside,
+                    );
+
+                    let (_, _, width, height) = Self::to_screen_pixels(ctx, rect);
+                    painter.text(
+                        rect.left_bottom() + egui::vec2(0.0, 6.0),
+                        egui::Align2::LEFT_TOP,
+                        format!("{} x {}", width, height),
+                        egui::FontId::monospace(14.0),
+                        egui::Color32::WHITE,
+                    );
+                }
+
+                if response.drag_stopped() {
+                    self.drag_start = None;
+                    if let Some(rect) = self.selection.filter(|rect| {
+                        rect.width() >= gui_const::OVERLAY_MIN_SELECTION
+                            && rect.height() >= gui_const::OVERLAY_MIN_SELECTION
+                    }) {
+                        *self.result.lock().unwrap() = Some(Self::to_screen_pixels(ctx, rect));
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                }
+            });
+    }
+}
+
+/// Opens a fullscreen overlay to drag out a crop region. Returns the region
+/// in screen pixels, or `None` if the user cancelled with Esc. With
+/// `snap_to_window`, corners near the focused window's edges land on them.
+pub fn select_region_overlay(snap_to_window: bool) -> anyhow::Result<Option<(i32, i32, i32, i32)>> {
+    let result = Arc::new(Mutex::new(None));
+    let overlay_result = Arc::clone(&result);
+
+    // Looked up before the overlay takes focus
+    let snap_window = if snap_to_window {
+        let window = crate::ScreenCapture::new()
+            .get_focused_window_bounds()
+            .ok()
+            .flatten();
+        if window.is_none() {
+            println!("No focused window found, corners won't snap");
+        }
+        window
+    } else {
+        None
+    };
+
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_fullscreen(true)
+            .with_transparent(true)
+            .with_decorations(false)
+            .with_always_on_top(),
+        ..Default::default()
+    };
+
+    eframe::run_native(
+        "Select Region",
+        options,
+        Box::new(move |_cc| {
+            Ok(Box::new(RegionOverlay {
+                drag_start: None,
+                selection: None,
+                snap_window,
+                result: overlay_result,
+            }))
+        }),
+    )
+    .map_err(|e| anyhow::anyhow!("Failed to open selection overlay: {}", e))?;
+
+    let region = *result.lock().unwrap();
+    Ok(region)
+}
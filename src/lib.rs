@@ -1,12 +1,22 @@
 pub mod constants;
+pub mod frames;
 pub mod gui;
+pub mod ocr;
 pub mod presets;
+pub mod source;
+pub mod stitch;
+pub mod stream;
 
 use anyhow::Result;
-use constants::timing;
-use crossterm::event::{Event, KeyCode, KeyEvent, poll, read};
+use constants::{display, overlap_detection, pdf, scroll, similarity, sticky, timing};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, poll, read};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, is_raw_mode_enabled};
 use enigo::{Enigo, Key, Keyboard, Settings};
-use image::{ImageBuffer, Rgba, RgbaImage};
+pub use frames::Frames;
+use image::{Rgba, RgbaImage};
+use indicatif::{ProgressBar, ProgressStyle};
+use source::ScreenSource;
+pub use source::{CaptureSource, MockSource};
 use std::thread;
 use std::time::Duration;
 
@@ -14,15 +24,382 @@ use std::time::Duration;
 use core_graphics::display::CGMainDisplayID;
 #[cfg(target_os = "macos")]
 use core_graphics::image::CGImageRef;
+#[cfg(target_os = "macos")]
+use image::ImageBuffer;
 
 #[cfg(target_os = "windows")]
-use windows::Win32::Foundation::{POINT, RECT};
+use windows::Win32::Foundation::{HWND, LPARAM, POINT, RECT};
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::HiDpi::{
+    DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2, DPI_AWARENESS_UNAWARE,
+    GetAwarenessFromDpiAwarenessContext, GetDpiForWindow, GetThreadDpiAwarenessContext,
+    SetProcessDpiAwarenessContext,
+};
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::FindWindowW;
 #[cfg(target_os = "windows")]
 use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
 #[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::{
+    EnumWindows, GetWindowTextW, IsIconic, IsWindowVisible, SW_RESTORE, SetForegroundWindow,
+    ShowWindow,
+};
+#[cfg(target_os = "windows")]
 use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowRect};
+#[cfg(target_os = "windows")]
+use windows::core::{BOOL, PCWSTR};
+
+#[cfg(target_os = "linux")]
+use x11rb::connection::Connection;
+#[cfg(target_os = "linux")]
+use x11rb::protocol::xproto::{
+    Atom, AtomEnum, ClientMessageEvent, ConnectionExt as _, EventMask, Window,
+};
+#[cfg(target_os = "linux")]
+use x11rb::rust_connection::RustConnection;
+
+pub const SUPPORTED_FORMATS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "bmp", "tiff", "tif", "webp", "pdf",
+];
+
+/// `--key` values with built-in handling, including the horizontal
+/// fallbacks. Anything else is parsed with `parse_key_sequence`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ScrollKey {
+    #[default]
+    Space,
+    Down,
+    PageDown,
+    Wheel,
+}
+
+impl ScrollKey {
+    pub const ALL: [ScrollKey; 4] = [
+        ScrollKey::Space,
+        ScrollKey::Down,
+        ScrollKey::PageDown,
+        ScrollKey::Wheel,
+    ];
+
+    /// The `--key` name
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ScrollKey::Space => "space",
+            ScrollKey::Down => "down",
+            ScrollKey::PageDown => "pagedown",
+            ScrollKey::Wheel => "wheel",
+        }
+    }
+
+    /// The key clicked to scroll in `direction`, or `None` to turn the
+    /// wheel instead. Space and PageDown have no horizontal counterpart, so
+    /// those fall back to the horizontal wheel.
+    pub fn as_key(self, direction: ScrollDirection) -> Option<Key> {
+        match (self, direction) {
+            (ScrollKey::Wheel, _) => None,
+            (ScrollKey::Space, ScrollDirection::Vertical) => Some(Key::Space),
+            (ScrollKey::Down, ScrollDirection::Vertical) => Some(Key::DownArrow),
+            (ScrollKey::PageDown, ScrollDirection::Vertical) => Some(Key::PageDown),
+            (ScrollKey::Down, ScrollDirection::Horizontal) => Some(Key::RightArrow),
+            (ScrollKey::Space | ScrollKey::PageDown, ScrollDirection::Horizontal) => None,
+        }
+    }
+}
+
+impl std::str::FromStr for ScrollKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase();
+        ScrollKey::ALL
+            .into_iter()
+            .find(|key| key.as_str() == name)
+            .ok_or_else(|| {
+                format!(
+                    "expected one of {}, got '{}'",
+                    ScrollKey::ALL.map(ScrollKey::as_str).join(", "),
+                    s
+                )
+            })
+    }
+}
+
+impl std::fmt::Display for ScrollKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What each scroll sends: a [`ScrollKey`], or a key sequence replayed
+/// exactly as given. Parsed once from a `--key` value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScrollInput {
+    Key(ScrollKey),
+    /// The sequence as written, for messages, and its chords
+    Sequence {
+        text: String,
+        chords: Vec<KeyChord>,
+    },
+}
+
+impl Default for ScrollInput {
+    fn default() -> Self {
+        ScrollInput::Key(ScrollKey::default())
+    }
+}
+
+impl From<ScrollKey> for ScrollInput {
+    fn from(key: ScrollKey) -> Self {
+        ScrollInput::Key(key)
+    }
+}
+
+impl std::str::FromStr for ScrollInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(key) = s.parse::<ScrollKey>() {
+            return Ok(ScrollInput::Key(key));
+        }
+        let chords = parse_key_sequence(s).map_err(|e| e.to_string())?;
+        Ok(ScrollInput::Sequence {
+            text: s.trim().to_string(),
+            chords,
+        })
+    }
+}
+
+impl std::fmt::Display for ScrollInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScrollInput::Key(key) => key.fmt(f),
+            ScrollInput::Sequence { text, .. } => f.write_str(text),
+        }
+    }
+}
+
+/// Progress bar shown during terminal captures. While set, terminal output
+/// goes through it so lines print above the bar instead of garbling it.
+static TERMINAL_PROGRESS: std::sync::Mutex<Option<ProgressBar>> = std::sync::Mutex::new(None);
+
+/// Set by `--log-json`: terminal log lines become `LogEvent` JSON objects
+/// on stderr instead
+static LOG_JSON: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Set by `--stdout`, where stdout carries the image: log lines go to
+/// stderr instead
+static LOG_TO_STDERR: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Set by `-v`: how many of the `[DEBUG]` diagnostics reach the terminal
+static VERBOSITY: std::sync::atomic::AtomicU8 = std::sync::atomic::AtomicU8::new(0);
+
+/// Terminal diagnostics level: 0 prints progress only, 1 adds retries and
+/// per-frame similarity and overlap results, 2 adds every image comparison
+pub fn set_verbosity(level: u8) {
+    VERBOSITY.store(level, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Switches terminal logging between human-readable lines on stdout and
+/// newline-delimited `LogEvent` JSON on stderr. The GUI log is unaffected.
+pub fn set_log_json(enabled: bool) {
+    LOG_JSON.store(enabled, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Sends terminal log lines to stderr, keeping stdout free for output data
+pub fn set_log_to_stderr(enabled: bool) {
+    LOG_TO_STDERR.store(enabled, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Whether terminal messages belong on stderr (see [`set_log_to_stderr`])
+pub fn log_to_stderr() -> bool {
+    LOG_TO_STDERR.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+/// Why a capture loop ended, as reported by `LogEvent::Stopped`
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    User,
+    MaxScrolls,
+    Identical,
+    Similar,
+    ContentOnly,
+    Stalled,
+    TimeLimit,
+}
+
+/// Machine-readable progress events written by `--log-json`, one JSON
+/// object per line, e.g.
+/// `{"ts":"2024-05-01T10:00:00.123+02:00","event":"captured","frame":3,"w":1920,"h":1080}`
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum LogEvent {
+    /// Any log line without a dedicated event
+    Message {
+        message: String,
+    },
+    Scrolling {
+        scroll: usize,
+    },
+    Captured {
+        frame: usize,
+        w: u32,
+        h: u32,
+    },
+    Stopped {
+        reason: StopReason,
+    },
+    Stitching {
+        frames: usize,
+    },
+    Done {
+        w: u32,
+        h: u32,
+    },
+}
+
+impl LogEvent {
+    fn emit(&self) {
+        #[derive(serde::Serialize)]
+        struct Record<'a> {
+            ts: String,
+            #[serde(flatten)]
+            event: &'a LogEvent,
+        }
+
+        let record = Record {
+            ts: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+            event: self,
+        };
+        if let Ok(line) = serde_json::to_string(&record) {
+            eprintln!("{}", line);
+        }
+    }
+}
+
+/// Prints a line to the terminal, above the progress bar if one is active
+fn print_line(msg: &str) {
+    // Raw mode turns off the terminal's newline translation, so each line
+    // has to return the cursor to column 0 itself
+    let raw = is_raw_mode_enabled().unwrap_or(false);
+    let msg = if raw {
+        msg.lines()
+            .map(|line| format!("\r{}", line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else {
+        msg.to_string()
+    };
+
+    let to_stderr = log_to_stderr();
+    match TERMINAL_PROGRESS.lock().unwrap().as_ref() {
+        Some(progress) => progress.println(msg),
+        None if raw && to_stderr => eprint!("{}\r\n", msg),
+        None if raw => print!("{}\r\n", msg),
+        None if to_stderr => eprintln!("{}", msg),
+        None => println!("{}", msg),
+    }
+}
+
+/// Prints a `[DEBUG]` line when the verbosity is at least `level`
+fn debug_line(level: u8, msg: &str) {
+    if VERBOSITY.load(std::sync::atomic::Ordering::Relaxed) >= level {
+        print_line(&format!("    [DEBUG] {}", msg));
+    }
+}
+
+/// Registers a progress bar for the capture loop and clears it when dropped,
+/// including on early returns through `?`
+struct ProgressGuard;
+
+impl ProgressGuard {
+    fn start(max_scrolls: Option<usize>) -> (Self, ProgressBar) {
+        let progress = match max_scrolls {
+            Some(max) => {
+                let bar = ProgressBar::new(max as u64);
+                bar.set_style(
+                    ProgressStyle::with_template(
+                        "{spinner} [{bar:30}] {pos}/{len} scrolls, ETA {eta}",
+                    )
+                    .unwrap()
+                    .progress_chars("=> "),
+                );
+                bar
+            }
+            None => {
+                let spinner = ProgressBar::new_spinner();
+                spinner.set_style(
+                    ProgressStyle::with_template("{spinner} {pos} scrolls (press Q to stop)")
+                        .unwrap(),
+                );
+                spinner
+            }
+        };
+        progress.enable_steady_tick(Duration::from_millis(timing::PROGRESS_TICK_MS));
+
+        *TERMINAL_PROGRESS.lock().unwrap() = Some(progress.clone());
+        (ProgressGuard, progress)
+    }
+}
+
+impl Drop for ProgressGuard {
+    fn drop(&mut self) {
+        if let Some(progress) = TERMINAL_PROGRESS.lock().unwrap().take() {
+            progress.finish_and_clear();
+        }
+    }
+}
+
+/// Keeps the terminal in raw mode while the capture loop polls for Q and
+/// Ctrl+C. Dropping it (also on early returns and panics) discards the keys
+/// pressed during the capture and restores the previous terminal mode.
+struct RawModeGuard;
+
+impl RawModeGuard {
+    fn enable() -> Result<Self> {
+        enable_raw_mode()
+            .map_err(|e| anyhow::anyhow!("Failed to enable raw terminal mode: {}", e))?;
+        Ok(RawModeGuard)
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        while poll(Duration::from_millis(0)).unwrap_or(false) {
+            let _ = read();
+        }
+        let _ = disable_raw_mode();
+    }
+}
+
+/// Per-capture state of the scroll loop, advanced one scroll at a time by
+/// `ScreenCapture::scroll_step`
+struct ScrollState {
+    // None for scripted sources, which need no input
+    enigo: Option<Enigo>,
+    key: ScrollInput,
+    // Resolved against the first frame
+    overlap: u32,
+    scroll_delay_ms: u64,
+    max_scrolls: Option<usize>,
+    wheel_clicks: i32,
+    crop_region: Option<(i32, i32, i32, i32)>,
+    stop_flag: Option<std::sync::Arc<std::sync::Mutex<bool>>>,
+    started: std::time::Instant,
+    scroll_count: usize,
+    stall_count: usize,
+    // Raw frames for --animate, kept only when requested
+    animation_frames: Vec<RgbaImage>,
+}
 
-pub const SUPPORTED_FORMATS: &[&str] = &["png", "jpg", "jpeg", "gif", "bmp", "tiff", "tif", "webp"];
+/// A frame taken by `ScreenCapture::scroll_step`
+struct ScrollFrame {
+    image: RgbaImage,
+    // The content didn't move, so the frame repeats the previous one
+    stalled: bool,
+    // How much of the overlap band changed, for the capture stats
+    diff_percentage: f32,
+}
 
 /// Validates that the format is supported
 pub fn validate_format(format: &str) -> Result<()> {
@@ -40,535 +417,4468 @@ pub fn validate_format(format: &str) -> Result<()> {
     }
 }
 
-/// Builds the full output path from filename and format
-pub fn build_output_path(filename: &str, format: &str) -> String {
-    let format_clean = format.trim_start_matches('.').to_lowercase();
-    format!("{}.{}", filename, format_clean)
+/// One step of a scroll key sequence: `key` is clicked while `modifiers`
+/// are held down
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyChord {
+    pub modifiers: Vec<Key>,
+    pub key: Key,
 }
 
-pub struct ScreenCapture {
-    #[cfg(target_os = "macos")]
-    display_id: u32,
-    #[cfg(target_os = "windows")]
-    _phantom: (),
+fn parse_modifier(name: &str) -> Option<Key> {
+    match name {
+        "ctrl" | "control" => Some(Key::Control),
+        "shift" => Some(Key::Shift),
+        "alt" | "option" => Some(Key::Alt),
+        "cmd" | "command" | "meta" | "super" | "win" => Some(Key::Meta),
+        _ => None,
+    }
 }
 
-impl ScreenCapture {
-    pub fn new() -> Self {
-        Self {
-            #[cfg(target_os = "macos")]
-            display_id: unsafe { CGMainDisplayID() },
-            #[cfg(target_os = "windows")]
-            _phantom: (),
+fn parse_key_name(name: &str) -> Option<Key> {
+    let key = match name {
+        "space" => Key::Space,
+        "down" => Key::DownArrow,
+        "up" => Key::UpArrow,
+        "left" => Key::LeftArrow,
+        "right" => Key::RightArrow,
+        "pagedown" | "pgdn" => Key::PageDown,
+        "pageup" | "pgup" => Key::PageUp,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "tab" => Key::Tab,
+        "enter" | "return" => Key::Return,
+        "esc" | "escape" => Key::Escape,
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Key::Unicode(c),
+                _ => return None,
+            }
         }
-    }
+    };
+    Some(key)
+}
 
-    pub fn parse_crop_region(crop_str: &str) -> Option<(i32, i32, i32, i32)> {
-        let parts: Vec<i32> = crop_str
-            .split(|c| c == ',' || c == ':' || c == ' ')
-            .filter_map(|s| s.trim().parse().ok())
-            .collect();
+/// Parses a scroll key sequence such as `"pagedown,pagedown"` or
+/// `"ctrl+down"`: chords are separated by commas and the keys of a chord by
+/// `+`, where the last key is clicked while the others are held as modifiers
+pub fn parse_key_sequence(sequence: &str) -> Result<Vec<KeyChord>> {
+    sequence
+        .split(',')
+        .map(|chord| {
+            let names: Vec<String> = chord
+                .split('+')
+                .map(|name| name.trim().to_lowercase())
+                .collect();
+            let (key_name, modifier_names) = names.split_last().unwrap();
 
-        if parts.len() == 4 && parts[2] > 0 && parts[3] > 0 {
-            Some((parts[0], parts[1], parts[2], parts[3]))
-        } else {
-            None
-        }
+            let key = parse_key_name(key_name).ok_or_else(|| {
+                anyhow::anyhow!("Unknown key '{}' in key sequence '{}'", key_name, sequence)
+            })?;
+            let modifiers = modifier_names
+                .iter()
+                .map(|name| {
+                    parse_modifier(name).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Unknown modifier '{}' in key sequence '{}'",
+                            name,
+                            sequence
+                        )
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok(KeyChord { modifiers, key })
+        })
+        .collect()
+}
+
+/// Converts a crop region from logical points to physical pixels
+pub fn scale_crop_region(crop: (i32, i32, i32, i32), scale: f64) -> (i32, i32, i32, i32) {
+    let scale_value = |value: i32| (value as f64 * scale).round() as i32;
+    (
+        scale_value(crop.0),
+        scale_value(crop.1),
+        scale_value(crop.2),
+        scale_value(crop.3),
+    )
+}
+
+/// Fits a crop region to a `width` x `height` screen. A negative x/y (e.g. a
+/// window dragged past the left or top edge) moves to 0 and shrinks the
+/// width/height by the same amount, so only the on-screen part is kept.
+/// Returns `None` when the adjusted region is empty or runs past the right
+/// or bottom edge.
+pub fn fit_crop_region(
+    crop: (i32, i32, i32, i32),
+    screen: (u32, u32),
+) -> Option<(u32, u32, u32, u32)> {
+    let (x, y, width, height) = crop;
+    let width = width as i64 + (x as i64).min(0);
+    let height = height as i64 + (y as i64).min(0);
+    let (x, y) = (x.max(0) as i64, y.max(0) as i64);
+
+    if width <= 0 || height <= 0 || x + width > screen.0 as i64 || y + height > screen.1 as i64 {
+        return None;
     }
+    Some((x as u32, y as u32, width as u32, height as u32))
+}
 
-    #[cfg(target_os = "macos")]
-    fn get_mouse_position() -> Result<(i32, i32)> {
-        let script = r#"
-tell application "System Events"
-    set mousePos to position of mouse
-    return (item 1 of mousePos) & "," & (item 2 of mousePos)
-end tell
-"#;
+/// Moves `point` onto the nearest edge of `window` (x, y, width, height)
+/// within `threshold` on each axis, if the point lies near the window at
+/// all. Returns the point and whether it moved.
+pub fn snap_to_window_edges(
+    point: (i32, i32),
+    window: (i32, i32, i32, i32),
+    threshold: i32,
+) -> ((i32, i32), bool) {
+    let (x, y) = point;
+    let (wx, wy, width, height) = window;
+    let near_window = (wx - threshold..=wx + width + threshold).contains(&x)
+        && (wy - threshold..=wy + height + threshold).contains(&y);
+    if !near_window {
+        return (point, false);
+    }
 
-        let output = std::process::Command::new("osascript")
-            .arg("-e")
-            .arg(script)
-            .output()?;
+    let snap = |value: i32, edges: [i32; 2]| {
+        edges
+            .into_iter()
+            .filter(|edge| (value - edge).abs() <= threshold)
+            .min_by_key(|edge| (value - edge).abs())
+            .unwrap_or(value)
+    };
+    let snapped = (snap(x, [wx, wx + width]), snap(y, [wy, wy + height]));
+    (snapped, snapped != point)
+}
 
-        if output.status.success() {
-            let result = String::from_utf8_lossy(&output.stdout);
-            let coords: Vec<i32> = result
-                .trim()
-                .split(',')
-                .filter_map(|s| s.trim().parse().ok())
-                .collect();
+/// Formats whose encoder honors the quality setting
+pub const LOSSY_FORMATS: &[&str] = &["jpg", "jpeg", "webp"];
 
-            if coords.len() == 2 {
-                return Ok((coords[0], coords[1]));
-            }
-        }
+/// Returns true if `format` is lossy, i.e. `--quality` applies to it
+pub fn is_lossy_format(format: &str) -> bool {
+    let format_lower = format.to_lowercase();
+    LOSSY_FORMATS.contains(&format_lower.trim_start_matches('.'))
+}
 
-        Err(anyhow::anyhow!("Failed to get mouse position"))
+/// Builds the full output path from filename and format
+pub fn build_output_path(filename: &str, format: &str) -> String {
+    let format_clean = format.trim_start_matches('.').to_lowercase();
+    format!("{}.{}", filename, format_clean)
+}
+
+/// `base` itself if nothing exists there yet, otherwise the first free
+/// `name (1).ext`, `name (2).ext`, ... next to it
+pub fn next_available_path(base: &std::path::Path) -> std::path::PathBuf {
+    if !base.exists() {
+        return base.to_path_buf();
     }
 
-    #[cfg(target_os = "windows")]
-    fn get_mouse_position() -> Result<(i32, i32)> {
-        unsafe {
-            let mut point = POINT { x: 0, y: 0 };
-            if GetCursorPos(&mut point).is_ok() {
-                Ok((point.x, point.y))
-            } else {
-                Err(anyhow::anyhow!("Failed to get cursor position"))
-            }
+    let stem = base
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = base
+        .extension()
+        .map(|ext| ext.to_string_lossy().into_owned());
+
+    (1..)
+        .map(|n| {
+            let file_name = match &extension {
+                Some(extension) => format!("{} ({}).{}", stem, n, extension),
+                None => format!("{} ({})", stem, n),
+            };
+            base.with_file_name(file_name)
+        })
+        .find(|candidate| !candidate.exists())
+        .unwrap()
+}
+
+/// Saves the image with the encoder matching the path's extension, applying
+/// `quality` (0-100) to JPEG and WebP. Other formats use their defaults.
+pub fn save_image(img: &RgbaImage, path: &str, quality: u8) -> Result<()> {
+    let extension = std::path::Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    match extension.as_str() {
+        "jpg" | "jpeg" => {
+            // JPEG has no alpha channel, so composite onto white first
+            let flattened = flatten_alpha(img);
+
+            let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+            let encoder =
+                image::codecs::jpeg::JpegEncoder::new_with_quality(file, quality.clamp(1, 100));
+            flattened.write_with_encoder(encoder)?;
+        }
+        "webp" => {
+            let encoded = webp::Encoder::from_rgba(img.as_raw(), img.width(), img.height())
+                .encode(quality.min(100) as f32);
+            std::fs::write(path, &*encoded)?;
         }
+        "pdf" => save_as_pdf(img, path, a4_page_height(img.width()))?,
+        _ => img.save(path)?,
     }
+    Ok(())
+}
 
-    #[cfg(target_os = "macos")]
-    fn enable_zoom() -> Result<()> {
-        let script = r#"
-tell application "System Events"
-    key code 28 using {command down, option down}
-end tell
-"#;
-        let _ = std::process::Command::new("osascript")
-            .arg("-e")
-            .arg(script)
-            .output();
+/// Extensions `save_animation` can write
+pub const ANIMATION_FORMATS: &[&str] = &["apng", "png", "webp"];
+
+fn animation_extension(path: &str) -> String {
+    std::path::Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+/// Validates that `path` has an extension `save_animation` supports
+pub fn validate_animation_path(path: &str) -> Result<()> {
+    if ANIMATION_FORMATS.contains(&animation_extension(path).as_str()) {
         Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "Unsupported animation format: '{}'\nSupported formats: {}",
+            path,
+            ANIMATION_FORMATS.join(", ")
+        ))
     }
+}
 
-    #[cfg(target_os = "windows")]
-    fn enable_zoom() -> Result<()> {
-        // Launch Windows Magnifier
-        let _ = std::process::Command::new("magnify.exe").spawn();
-        Ok(())
+/// Encodes `frames` as a looping animation at `fps` frames per second:
+/// APNG for .apng/.png paths, animated WebP for .webp. Frames whose size
+/// differs from the first one are skipped, since both formats need a fixed
+/// canvas.
+pub fn save_animation(frames: &[RgbaImage], path: &str, fps: u32) -> Result<usize> {
+    validate_animation_path(path)?;
+    let Some(first) = frames.first() else {
+        return Err(anyhow::anyhow!("No frames to animate"));
+    };
+    let (width, height) = first.dimensions();
+    let frames: Vec<&RgbaImage> = frames
+        .iter()
+        .filter(|frame| frame.dimensions() == (width, height))
+        .collect();
+    let fps = fps.max(1);
+
+    if animation_extension(path) == "webp" {
+        let config = webp::WebPConfig::new()
+            .map_err(|_| anyhow::anyhow!("Failed to initialize the WebP encoder"))?;
+        let mut encoder = webp::AnimEncoder::new(width, height, &config);
+        let frame_ms = (1000 / fps) as i32;
+        for (i, frame) in frames.iter().enumerate() {
+            encoder.add_frame(webp::AnimFrame::from_rgba(
+                frame.as_raw(),
+                width,
+                height,
+                i as i32 * frame_ms,
+            ));
+        }
+        let encoded = encoder
+            .try_encode()
+            .map_err(|e| anyhow::anyhow!("Failed to encode WebP animation: {:?}", e))?;
+        std::fs::write(path, &*encoded)?;
+    } else {
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let mut encoder = png::Encoder::new(file, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        // 0 plays = loop forever
+        encoder.set_animated(frames.len() as u32, 0)?;
+        encoder.set_frame_delay(1, fps.min(u16::MAX as u32) as u16)?;
+
+        let mut writer = encoder.write_header()?;
+        for frame in &frames {
+            writer.write_image_data(frame.as_raw())?;
+        }
+        writer.finish()?;
     }
 
-    fn show_live_coordinates() -> Result<(i32, i32)> {
-        use std::io::{self, Write};
+    Ok(frames.len())
+}
 
-        println!("   Live coordinates (move mouse, press ENTER to select):");
-        println!("   ┌─────────────────────────────────────────┐");
+/// Puts the image on the system clipboard. On Linux the contents are served
+/// by this process and vanish when it exits, so a short-lived caller passes
+/// `wait` to block until a clipboard manager or another copy replaces them.
+/// `wait` has no effect elsewhere.
+pub fn copy_to_clipboard(img: &RgbaImage, wait: bool) -> Result<()> {
+    let mut clipboard = arboard::Clipboard::new()
+        .map_err(|e| anyhow::anyhow!("Failed to open clipboard: {}", e))?;
+    let image = arboard::ImageData {
+        width: img.width() as usize,
+        height: img.height() as usize,
+        bytes: std::borrow::Cow::Borrowed(img.as_raw()),
+    };
 
-        // Show live coordinates until Enter is pressed
-        let (tx, rx) = std::sync::mpsc::channel();
+    #[cfg(target_os = "linux")]
+    let result = if wait {
+        use arboard::SetExtLinux;
+        clipboard.set().wait().image(image)
+    } else {
+        clipboard.set_image(image)
+    };
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = wait;
+        clipboard.set_image(image)
+    };
 
-        std::thread::spawn(move || {
-            let mut input = String::new();
-            let _ = io::stdin().read_line(&mut input);
-            let _ = tx.send(());
-        });
+    result.map_err(|e| anyhow::anyhow!("Failed to copy image to clipboard: {}", e))
+}
 
-        loop {
-            if let Ok((x, y)) = Self::get_mouse_position() {
-                print!("\r   │ Current position: ({:4}, {:4})          │", x, y);
-                io::stdout().flush()?;
-            }
+/// Errors returned by the public `ScreenCapture` methods
+#[derive(Debug, thiserror::Error)]
+pub enum CaptureError {
+    /// No display could be listed, e.g. for lack of Screen Recording
+    /// permission or in a headless session
+    #[error("No screen found ({cause}). {}", ScreenCapture::no_screen_hint())]
+    NoScreen { cause: String },
 
-            // Check if Enter was pressed
-            if rx.try_recv().is_ok() {
-                let (x, y) = Self::get_mouse_position()?;
-                println!("\r   └─────────────────────────────────────────┘");
-                return Ok((x, y));
-            }
+    /// A crop region (x, y, width, height) that doesn't lie on the
+    /// captured screen (width, height), in physical pixels
+    #[error(
+        "Crop region {}x{} at ({}, {}) does not fit the {}x{} screen",
+        .crop.2, .crop.3, .crop.0, .crop.1, .screen.0, .screen.1
+    )]
+    CropOutOfBounds {
+        crop: (i32, i32, i32, i32),
+        screen: (u32, u32),
+    },
 
-            thread::sleep(Duration::from_millis(timing::MOUSE_POSITION_POLL_MS));
-        }
-    }
+    #[error("Invalid crop region '{0}'. Use: x,y,width,height (e.g., '100,50,1920,1080')")]
+    InvalidCropFormat(String),
 
-    pub fn select_region_interactive() -> Result<(i32, i32, i32, i32)> {
-        use std::io::{self, Write};
+    /// A crop region (x, y, width, height), or the full screen when `None`,
+    /// that produced a frame with no pixels
+    #[error("Captured an empty frame from {}; check the crop size", describe_crop(.crop))]
+    EmptyFrame { crop: Option<(i32, i32, i32, i32)> },
 
-        println!("\nINTERACTIVE REGION SELECTION");
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-        println!();
+    /// Keyboard/mouse simulation could not be set up, usually for lack of
+    /// permission. Holds the underlying error message.
+    #[error(
+        "Cannot simulate keyboard/mouse input ({0}).\n{hint}",
+        hint = input_permission_hint()
+    )]
+    InputPermissionDenied(String),
 
-        #[cfg(target_os = "macos")]
-        {
-            println!("TIP: Press Option+Command+8 to toggle macOS Zoom (magnifier)");
-            println!("     Option+Command+= to zoom in, Option+Command+- to zoom out");
+    /// Sending a key press or scroll event failed mid-capture
+    #[error("Failed to send input: {0}")]
+    InputFailed(#[from] enigo::InputError),
+
+    /// Any other failure (I/O, encoding, platform APIs)
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl From<anyhow::Error> for CaptureError {
+    /// Unwraps a `CaptureError` raised inside anyhow-based internals
+    /// instead of burying it in `Other`
+    fn from(error: anyhow::Error) -> Self {
+        match error.downcast::<CaptureError>() {
+            Ok(error) => error,
+            Err(error) => CaptureError::Other(error),
         }
+    }
+}
 
-        #[cfg(target_os = "windows")]
-        {
-            println!("TIP: Press Win+Plus to open Windows Magnifier");
-            println!("     Win+Plus/Minus to zoom in/out");
+fn describe_crop(crop: &Option<(i32, i32, i32, i32)>) -> String {
+    match crop {
+        Some((x, y, width, height)) => {
+            format!("crop region {}x{} at ({}, {})", width, height, x, y)
         }
+        None => "the full screen".to_string(),
+    }
+}
 
-        println!();
+#[cfg(target_os = "macos")]
+fn input_permission_hint() -> &'static str {
+    "Grant Accessibility access to this app (or the terminal running it) in System Settings > Privacy & Security > Accessibility, then restart it."
+}
+
+#[cfg(target_os = "linux")]
+fn input_permission_hint() -> &'static str {
+    "Check that DISPLAY points to a running X server; on Wayland, run from an XWayland session or a compositor that allows input emulation."
+}
+
+#[cfg(target_os = "windows")]
+fn input_permission_hint() -> &'static str {
+    "Windows blocks input to windows of elevated apps; if the target runs as administrator, run capture as administrator too."
+}
+
+/// Details of a finished capture, embedded as PNG text chunks on save
+#[derive(Clone, Debug)]
+pub struct CaptureMetadata {
+    pub capture_time: chrono::DateTime<chrono::Local>,
+    pub crop_region: Option<(i32, i32, i32, i32)>,
+    pub scroll_count: usize,
+}
+
+impl CaptureMetadata {
+    /// Keyword/text pairs in the order they are written
+    pub fn text_chunks(&self) -> Vec<(String, String)> {
+        let crop_region = match self.crop_region {
+            Some((x, y, w, h)) => format!("{},{},{},{}", x, y, w, h),
+            None => "full screen".to_string(),
+        };
+
+        vec![
+            ("CaptureTime".to_string(), self.capture_time.to_rfc3339()),
+            ("CropRegion".to_string(), crop_region),
+            ("ScrollCount".to_string(), self.scroll_count.to_string()),
+            (
+                "Software".to_string(),
+                format!("capture {}", env!("CARGO_PKG_VERSION")),
+            ),
+        ]
+    }
+}
+
+/// Diagnostics of a finished scroll capture, for comparing settings across
+/// runs (`--stats-json`)
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct CaptureStats {
+    /// Frames stitched into the result
+    pub frames: usize,
+    /// Overlap used between each pair of stitched frames
+    pub overlaps: Vec<u32>,
+    /// Percent of the overlap band that changed, one entry per scroll
+    pub diff_percentages: Vec<f32>,
+    pub final_size: [u32; 2],
+    pub duration_ms: u64,
+}
+
+/// What [`ScreenCapture::check_permissions`] found before a capture
+#[derive(Clone, Debug, Default)]
+pub struct PermissionStatus {
+    /// The mouse position could be read
+    pub mouse_position: bool,
+    /// A frame of the screen could be captured
+    pub screen_capture: bool,
+    /// A keyboard/mouse event could be sent; `None` when not checked
+    /// because the capture sends no input
+    pub input_events: Option<bool>,
+    /// How to grant each missing permission, without repeats
+    pub remediation: Vec<String>,
+}
+
+impl PermissionStatus {
+    pub fn all_granted(&self) -> bool {
+        self.mouse_position && self.screen_capture && self.input_events != Some(false)
+    }
+
+    /// Records `hint` when `granted` is false and passes `granted` through
+    fn require(&mut self, granted: bool, hint: &str) -> bool {
+        if !granted && !self.remediation.iter().any(|known| known == hint) {
+            self.remediation.push(hint.to_string());
+        }
+        granted
+    }
+}
+
+impl std::fmt::Display for PermissionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (granted, check) in [
+            (Some(self.mouse_position), "Read the mouse position"),
+            (Some(self.screen_capture), "Capture the screen"),
+            (self.input_events, "Send keyboard/mouse input"),
+        ] {
+            let Some(granted) = granted else {
+                continue;
+            };
+            writeln!(f, "  {} {}", if granted { "✓" } else { "✗" }, check)?;
+        }
+        for hint in &self.remediation {
+            writeln!(f, "{}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+/// Saves an RGBA PNG with the capture metadata as tEXt chunks
+pub fn save_image_with_metadata(
+    img: &RgbaImage,
+    path: &str,
+    metadata: &CaptureMetadata,
+) -> Result<()> {
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let mut encoder = png::Encoder::new(file, img.width(), img.height());
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    for (keyword, text) in metadata.text_chunks() {
+        encoder.add_text_chunk(keyword, text)?;
+    }
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(img.as_raw())?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// Single-channel copy of the image for text-heavy pages: plain grayscale,
+/// or bilevel when `threshold` is set (luma above it turns white, the rest
+/// black)
+pub fn to_luma(img: &RgbaImage, threshold: Option<u8>) -> image::GrayImage {
+    let mut gray = image::imageops::grayscale(img);
+    if let Some(cutoff) = threshold {
+        for pixel in gray.pixels_mut() {
+            pixel.0[0] = if pixel.0[0] > cutoff { 255 } else { 0 };
+        }
+    }
+    gray
+}
+
+/// Composites the image onto a white background, dropping the alpha channel
+fn flatten_alpha(img: &RgbaImage) -> image::RgbImage {
+    image::RgbImage::from_fn(img.width(), img.height(), |x, y| {
+        let [r, g, b, a] = img.get_pixel(x, y).0;
+        let blend = |c: u8| ((c as u32 * a as u32 + 255 * (255 - a as u32)) / 255) as u8;
+        image::Rgb([blend(r), blend(g), blend(b)])
+    })
+}
+
+/// Cuts a tall image into consecutive slices at most `max_height` rows tall,
+/// each repeating the last `overlap` rows of the one before so no content
+/// falls on a cut. Images that already fit come back as a single slice.
+pub fn split_tall_image(img: &RgbaImage, max_height: u32, overlap: u32) -> Vec<RgbaImage> {
+    let max_height = max_height.max(1);
+    if img.height() <= max_height {
+        return vec![img.clone()];
+    }
+
+    // Each slice has to advance by at least one row
+    let step = max_height - overlap.min(max_height - 1);
+    let mut parts = Vec::new();
+    let mut top = 0;
+    loop {
+        let height = max_height.min(img.height() - top);
+        parts.push(image::imageops::crop_imm(img, 0, top, img.width(), height).to_image());
+        if top + height >= img.height() {
+            break;
+        }
+        top += step;
+    }
+    parts
+}
+
+/// Makes the process per-monitor DPI aware on Windows, so window rects and
+/// captured frames share physical pixel coordinates on scaled displays.
+/// Call it once at startup, before any window is created; it fails
+/// harmlessly if the awareness was already set (e.g. by a manifest). A
+/// no-op on other platforms.
+pub fn enable_dpi_awareness() {
+    #[cfg(target_os = "windows")]
+    unsafe {
+        let _ = SetProcessDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2);
+    }
+}
+
+/// Scales a window rect from logical units (`BASE_DPI`) to the physical
+/// pixels of a display at `dpi`, e.g. 144 at 150% scaling
+#[cfg(any(target_os = "windows", test))]
+fn scale_rect_for_dpi(rect: (i32, i32, i32, i32), dpi: u32) -> (i32, i32, i32, i32) {
+    let base = constants::display::BASE_DPI as i64;
+    let scale = |value: i32| ((value as i64 * dpi as i64 + base / 2).div_euclid(base)) as i32;
+    (scale(rect.0), scale(rect.1), scale(rect.2), scale(rect.3))
+}
+
+/// Resizes the stitched image by `factor` with Lanczos3, e.g. 0.5 for half
+/// resolution. Each side stays at least one pixel.
+pub fn scale_image(img: &RgbaImage, factor: f32) -> RgbaImage {
+    let scale = |length: u32| ((length as f32 * factor).round() as u32).max(1);
+    image::imageops::resize(
+        img,
+        scale(img.width()),
+        scale(img.height()),
+        image::imageops::FilterType::Lanczos3,
+    )
+}
+
+/// Crops away uniform-colored rows and columns around the edges, such as
+/// window padding, comparing each edge against its first pixel with
+/// `tolerance` per channel. An axis is left untouched when trimming it
+/// would remove more than `MAX_TRIM_FRACTION` of it.
+pub fn autotrim(img: &RgbaImage, tolerance: u8) -> RgbaImage {
+    use constants::autotrim::MAX_TRIM_FRACTION;
+
+    let (width, height) = img.dimensions();
+    if width == 0 || height == 0 {
+        return img.clone();
+    }
+
+    let matches = |pixel: &Rgba<u8>, reference: &Rgba<u8>| {
+        (0..4).all(|c| pixel[c].abs_diff(reference[c]) <= tolerance)
+    };
+    let uniform_row =
+        |y: u32, reference: &Rgba<u8>| (0..width).all(|x| matches(img.get_pixel(x, y), reference));
+    let uniform_column =
+        |x: u32, reference: &Rgba<u8>| (0..height).all(|y| matches(img.get_pixel(x, y), reference));
+
+    let top_color = *img.get_pixel(0, 0);
+    let top = (0..height)
+        .take_while(|&y| uniform_row(y, &top_color))
+        .count() as u32;
+    let bottom_color = *img.get_pixel(0, height - 1);
+    let bottom = (top..height)
+        .rev()
+        .take_while(|&y| uniform_row(y, &bottom_color))
+        .count() as u32;
+    let left_color = *img.get_pixel(0, 0);
+    let left = (0..width)
+        .take_while(|&x| uniform_column(x, &left_color))
+        .count() as u32;
+    let right_color = *img.get_pixel(width - 1, 0);
+    let right = (left..width)
+        .rev()
+        .take_while(|&x| uniform_column(x, &right_color))
+        .count() as u32;
+
+    let within_limit =
+        |trimmed: u32, total: u32| trimmed as f32 <= total as f32 * MAX_TRIM_FRACTION;
+    let (top, bottom) = if within_limit(top + bottom, height) {
+        (top, bottom)
+    } else {
+        (0, 0)
+    };
+    let (left, right) = if within_limit(left + right, width) {
+        (left, right)
+    } else {
+        (0, 0)
+    };
+
+    image::imageops::crop_imm(img, left, top, width - left - right, height - top - bottom)
+        .to_image()
+}
+
+/// Page height in pixels that gives an A4 aspect ratio for an image this wide
+pub fn a4_page_height(width: u32) -> u32 {
+    ((width as f32 * pdf::A4_HEIGHT_MM / pdf::A4_WIDTH_MM).round() as u32).max(1)
+}
+
+/// Slices the image into `page_height_px` tall chunks and writes one PDF page
+/// per chunk. Pages are A4 wide; the last, shorter chunk keeps its scale and
+/// sits at the top of a full-size page.
+pub fn save_as_pdf(img: &RgbaImage, path: &str, page_height_px: u32) -> Result<()> {
+    use printpdf::{
+        ColorBits, ColorSpace, Image, ImageTransform, ImageXObject, Mm, PdfDocument, Px,
+    };
+
+    if page_height_px == 0 {
+        return Err(anyhow::anyhow!("PDF page height must be at least 1 pixel"));
+    }
+
+    // Choose the DPI so the image width exactly fills the page width
+    let dpi = img.width() as f32 / (pdf::A4_WIDTH_MM / pdf::MM_PER_INCH);
+    let px_to_mm = |px: u32| px as f32 / dpi * pdf::MM_PER_INCH;
+    let page_width = Mm(pdf::A4_WIDTH_MM);
+    let page_height = Mm(px_to_mm(page_height_px));
+
+    let (document, first_page, first_layer) =
+        PdfDocument::new("Scroll capture", page_width, page_height, "Layer 1");
+    let flattened = flatten_alpha(img);
+
+    for (index, top) in (0..img.height())
+        .step_by(page_height_px as usize)
+        .enumerate()
+    {
+        let chunk_height = page_height_px.min(img.height() - top);
+        let (page, layer) = if index == 0 {
+            (first_page, first_layer)
+        } else {
+            document.add_page(page_width, page_height, "Layer 1")
+        };
+
+        let chunk =
+            image::imageops::crop_imm(&flattened, 0, top, img.width(), chunk_height).to_image();
+        let xobject = ImageXObject {
+            width: Px(img.width() as usize),
+            height: Px(chunk_height as usize),
+            color_space: ColorSpace::Rgb,
+            bits_per_component: ColorBits::Bit8,
+            interpolate: false,
+            image_data: chunk.into_raw(),
+            image_filter: None,
+            smask: None,
+            clipping_bbox: None,
+        };
+
+        // PDF coordinates start at the bottom-left, so lift the chunk to the top
+        Image::from(xobject).add_to_layer(
+            document.get_page(page).get_layer(layer),
+            ImageTransform {
+                translate_y: Some(Mm(page_height.0 - px_to_mm(chunk_height))),
+                dpi: Some(dpi),
+                ..Default::default()
+            },
+        );
+    }
+
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+    document
+        .save(&mut writer)
+        .map_err(|e| anyhow::anyhow!("Failed to write PDF: {}", e))?;
+    Ok(())
+}
+
+/// Quantizes the image to at most `colors` colors and saves it as an indexed PNG
+pub fn save_palette_png(img: &RgbaImage, path: &str, colors: usize) -> Result<()> {
+    if !(2..=256).contains(&colors) {
+        return Err(anyhow::anyhow!(
+            "Palette size must be between 2 and 256 colors, got {}",
+            colors
+        ));
+    }
+
+    // Sample factor 10 is NeuQuant's recommended speed/quality trade-off
+    let quantizer = color_quant::NeuQuant::new(10, colors, img.as_raw());
+    let indices: Vec<u8> = img
+        .as_raw()
+        .chunks_exact(4)
+        .map(|pixel| quantizer.index_of(pixel) as u8)
+        .collect();
+
+    let color_map = quantizer.color_map_rgba();
+    let palette: Vec<u8> = color_map
+        .chunks_exact(4)
+        .flat_map(|c| [c[0], c[1], c[2]])
+        .collect();
+    let alpha: Vec<u8> = color_map.chunks_exact(4).map(|c| c[3]).collect();
+
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let mut encoder = png::Encoder::new(file, img.width(), img.height());
+    encoder.set_color(png::ColorType::Indexed);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_palette(palette);
+    if alpha.iter().any(|&a| a != 255) {
+        encoder.set_trns(alpha);
+    }
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&indices)?;
+    writer.finish()?;
+    Ok(())
+}
+
+// 3x5 bitmaps for the digits 0-9, one row per entry (bit 2 = left column)
+const DIGIT_GLYPHS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+fn put_pixel_checked(img: &mut RgbaImage, x: i32, y: i32, color: Rgba<u8>) {
+    if x >= 0 && y >= 0 && (x as u32) < img.width() && (y as u32) < img.height() {
+        img.put_pixel(x as u32, y as u32, color);
+    }
+}
+
+fn draw_line(img: &mut RgbaImage, from: (i32, i32), to: (i32, i32), color: Rgba<u8>) {
+    let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs()).max(1);
+    for step in 0..=steps {
+        let x = from.0 + (to.0 - from.0) * step / steps;
+        let y = from.1 + (to.1 - from.1) * step / steps;
+        for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            put_pixel_checked(img, x + dx, y + dy, color);
+        }
+    }
+}
+
+fn draw_number(img: &mut RgbaImage, number: usize, x: i32, y: i32, scale: i32, color: Rgba<u8>) {
+    for (i, digit) in number.to_string().bytes().enumerate() {
+        let glyph = DIGIT_GLYPHS[(digit - b'0') as usize];
+        let glyph_x = x + i as i32 * 4 * scale;
+        for (row, bits) in glyph.iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) != 0 {
+                    for sy in 0..scale {
+                        for sx in 0..scale {
+                            put_pixel_checked(
+                                img,
+                                glyph_x + col * scale + sx,
+                                y + row as i32 * scale + sy,
+                                color,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns a copy of `img` with the cursor path drawn over it and each
+/// position marked with its frame number.
+pub fn draw_cursor_trace(img: &RgbaImage, points: &[(i32, i32)]) -> RgbaImage {
+    const MARKER_RADIUS: i32 = 7;
+    let path_color = Rgba([255, 0, 0, 255]);
+
+    let mut annotated = img.clone();
+
+    for pair in points.windows(2) {
+        draw_line(&mut annotated, pair[0], pair[1], path_color);
+    }
+
+    for (i, &(x, y)) in points.iter().enumerate() {
+        for dy in -MARKER_RADIUS..=MARKER_RADIUS {
+            for dx in -MARKER_RADIUS..=MARKER_RADIUS {
+                if dx * dx + dy * dy <= MARKER_RADIUS * MARKER_RADIUS {
+                    put_pixel_checked(&mut annotated, x + dx, y + dy, path_color);
+                }
+            }
+        }
+        draw_number(
+            &mut annotated,
+            i + 1,
+            x + MARKER_RADIUS + 2,
+            y - MARKER_RADIUS,
+            3,
+            path_color,
+        );
+    }
+
+    annotated
+}
+
+/// The window that had focus before a batch run, restored once it finishes
+pub struct FocusedWindow {
+    #[cfg(target_os = "macos")]
+    app_name: String,
+    #[cfg(target_os = "windows")]
+    hwnd: isize,
+    #[cfg(target_os = "linux")]
+    window: u32,
+}
+
+/// Axis along which the page is scrolled and frames are stitched
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ScrollDirection {
+    #[default]
+    Vertical,
+    Horizontal,
+}
+
+impl ScrollDirection {
+    /// Extent of the image along the scroll axis
+    fn length(self, img: &RgbaImage) -> u32 {
+        match self {
+            ScrollDirection::Vertical => img.height(),
+            ScrollDirection::Horizontal => img.width(),
+        }
+    }
+
+    /// Extent of the image across the scroll axis
+    fn breadth(self, img: &RgbaImage) -> u32 {
+        match self {
+            ScrollDirection::Vertical => img.width(),
+            ScrollDirection::Horizontal => img.height(),
+        }
+    }
+
+    /// Rotates horizontal frames a quarter turn clockwise so the right edge
+    /// becomes the bottom, letting the vertical comparison code treat the
+    /// newly revealed strip the same way in both directions.
+    fn scan_view(self, img: &RgbaImage) -> std::borrow::Cow<'_, RgbaImage> {
+        match self {
+            ScrollDirection::Vertical => std::borrow::Cow::Borrowed(img),
+            ScrollDirection::Horizontal => std::borrow::Cow::Owned(image::imageops::rotate90(img)),
+        }
+    }
+}
+
+/// Overlap between consecutive frames: a fixed pixel count, a percentage
+/// of the frame height (width when horizontal), or detected per frame pair
+/// with `Auto`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OverlapSetting {
+    Auto,
+    Fixed(u32),
+    Percent(f32),
+}
+
+impl OverlapSetting {
+    pub fn is_auto(self) -> bool {
+        self == OverlapSetting::Auto
+    }
+
+    /// Fixed overlap in pixels; the default overlap stands in as the fallback
+    /// for frame pairs where auto detection finds no match, and for a
+    /// percentage until the frame size is known
+    pub fn pixels(self) -> u32 {
+        match self {
+            OverlapSetting::Auto | OverlapSetting::Percent(_) => constants::defaults::OVERLAP,
+            OverlapSetting::Fixed(pixels) => pixels,
+        }
+    }
+
+    /// The percentage of a `Percent` overlap; see
+    /// [`ScreenCapture::with_overlap_percent`]
+    pub fn percent(self) -> Option<f32> {
+        match self {
+            OverlapSetting::Percent(percent) => Some(percent),
+            _ => None,
+        }
+    }
+}
+
+impl std::str::FromStr for OverlapSetting {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().eq_ignore_ascii_case("auto") {
+            return Ok(OverlapSetting::Auto);
+        }
+        if let Some(percent) = s.trim().strip_suffix('%') {
+            return match percent.trim().parse::<f32>() {
+                Ok(percent) if (0.0..100.0).contains(&percent) => {
+                    Ok(OverlapSetting::Percent(percent))
+                }
+                _ => Err(format!("expected a percentage below 100%, got '{}'", s)),
+            };
+        }
+        s.trim().parse().map(OverlapSetting::Fixed).map_err(|_| {
+            format!(
+                "expected a pixel count, a percentage or 'auto', got '{}'",
+                s
+            )
+        })
+    }
+}
+
+impl std::fmt::Display for OverlapSetting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OverlapSetting::Auto => write!(f, "auto"),
+            OverlapSetting::Fixed(pixels) => write!(f, "{} pixels", pixels),
+            OverlapSetting::Percent(percent) => write!(f, "{}% of the frame", percent),
+        }
+    }
+}
+
+/// How two frames are compared when looking for the end of the content and
+/// scoring overlap candidates
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SimilarityMetric {
+    /// Pixel-exact comparison (with `--pixel-tolerance` and `--diff-threshold`)
+    ExactDiff,
+    /// Pixels whose channels differ by at most this much count as equal;
+    /// the same as `ExactDiff` with this `--pixel-tolerance`
+    Tolerance(u8),
+    /// Structural similarity over the overlap band; frames match at or
+    /// above `threshold` (0-1)
+    Ssim { threshold: f32 },
+}
+
+impl std::str::FromStr for SimilarityMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (name, value) = match s.split_once(':') {
+            Some((name, value)) => (name, Some(value.trim())),
+            None => (s, None),
+        };
+        match (name.to_ascii_lowercase().as_str(), value) {
+            ("exact", None) => Ok(SimilarityMetric::ExactDiff),
+            ("tolerance", Some(value)) => value
+                .parse()
+                .map(SimilarityMetric::Tolerance)
+                .map_err(|_| format!("expected a tolerance of 0-255, got '{}'", value)),
+            ("ssim", None) => Ok(SimilarityMetric::Ssim {
+                threshold: similarity::SSIM_THRESHOLD,
+            }),
+            ("ssim", Some(value)) => match value.parse::<f32>() {
+                Ok(threshold) if (0.0..=1.0).contains(&threshold) => {
+                    Ok(SimilarityMetric::Ssim { threshold })
+                }
+                _ => Err(format!(
+                    "expected an SSIM threshold of 0-1, got '{}'",
+                    value
+                )),
+            },
+            _ => Err(format!(
+                "expected 'exact', 'tolerance:N' or 'ssim[:THRESHOLD]', got '{}'",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for SimilarityMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SimilarityMetric::ExactDiff => write!(f, "exact"),
+            SimilarityMetric::Tolerance(tolerance) => write!(f, "tolerance:{}", tolerance),
+            SimilarityMetric::Ssim { threshold } => write!(f, "ssim:{}", threshold),
+        }
+    }
+}
+
+/// Where frames come from
+enum Backend {
+    /// The `screenshots` crate (X11, macOS, Windows)
+    Screenshots,
+    /// xdg-desktop-portal, for Wayland sessions where X11 capture fails
+    #[cfg(target_os = "linux")]
+    Portal,
+}
+
+impl Backend {
+    #[cfg(target_os = "linux")]
+    fn detect() -> Self {
+        let wayland = std::env::var("XDG_SESSION_TYPE")
+            .is_ok_and(|session| session.eq_ignore_ascii_case("wayland"));
+        if wayland {
+            Backend::Portal
+        } else {
+            Backend::Screenshots
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn detect() -> Self {
+        Backend::Screenshots
+    }
+}
+
+/// Edge bands (in pixels) that are excluded from end-of-content detection,
+/// such as an animated scrollbar on the right or sticky header/footer rows.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExcludedBands {
+    pub top: u32,
+    pub bottom: u32,
+    pub right: u32,
+}
+
+/// Settings for one scroll capture, for use with [`ScreenCapture::run`].
+/// The direction and pixel tolerance are set on the [`ScreenCapture`]
+/// instead, since the video and stitching modes use them too.
+///
+/// ```no_run
+/// use capture::{CaptureOptions, ScreenCapture, ScrollKey};
+///
+/// let options = CaptureOptions::new()
+///     .with_overlap(150)
+///     .with_max_scrolls(Some(10))
+///     .with_key(ScrollKey::PageDown.into());
+/// let image = ScreenCapture::new().run(options)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct CaptureOptions {
+    overlap: u32,
+    max_scrolls: Option<usize>,
+    delay: u64,
+    key: ScrollInput,
+    window_only: bool,
+    crop: Option<String>,
+    scroll_delay_ms: u64,
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        Self {
+            overlap: constants::defaults::OVERLAP,
+            max_scrolls: None,
+            delay: constants::defaults::DELAY,
+            key: ScrollInput::default(),
+            window_only: false,
+            crop: None,
+            scroll_delay_ms: constants::defaults::SCROLL_DELAY,
+        }
+    }
+}
+
+impl CaptureOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_overlap(mut self, overlap: u32) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    /// `None` scrolls until the end of the content is detected
+    pub fn with_max_scrolls(mut self, max_scrolls: Option<usize>) -> Self {
+        self.max_scrolls = max_scrolls;
+        self
+    }
+
+    /// Seconds to wait before the first capture
+    pub fn with_delay(mut self, delay: u64) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Scroll key: a [`ScrollKey`], or a key sequence parsed from text
+    /// such as "ctrl+down"
+    pub fn with_key(mut self, key: ScrollInput) -> Self {
+        self.key = key;
+        self
+    }
+
+    pub fn with_window_only(mut self, window_only: bool) -> Self {
+        self.window_only = window_only;
+        self
+    }
+
+    /// Crop region as "x,y,width,height" or a preset name
+    pub fn with_crop(mut self, crop: Option<String>) -> Self {
+        self.crop = crop;
+        self
+    }
+
+    pub fn with_scroll_delay(mut self, scroll_delay_ms: u64) -> Self {
+        self.scroll_delay_ms = scroll_delay_ms;
+        self
+    }
+}
+
+/// See [`ScreenCapture::with_frame_observer`]
+type FrameObserver = Box<dyn Fn(&RgbaImage) + Send + Sync>;
+
+/// See [`ScreenCapture::with_progress_observer`]
+type ProgressObserver = Box<dyn Fn(f32) + Send + Sync>;
+
+pub struct ScreenCapture {
+    #[cfg(target_os = "macos")]
+    display_id: u32,
+    #[cfg(target_os = "windows")]
+    _phantom: (),
+    display_index: usize,
+    trim_first_top: u32,
+    sticky_agreement: Option<f32>,
+    grayscale: bool,
+    excluded_bands: ExcludedBands,
+    end_on_content_only: bool,
+    trace_cursor: bool,
+    cursor_trace: std::sync::Mutex<Vec<(i32, i32)>>,
+    adaptive_threshold: bool,
+    diff_threshold: Option<f32>,
+    strict_crop: bool,
+    auto_overlap: bool,
+    overlap_cache: std::sync::Mutex<Option<u32>>,
+    // The display looked up on first use, so the scroll loop doesn't
+    // re-enumerate screens for every frame
+    screen_cache: std::sync::Mutex<Option<screenshots::Screen>>,
+    // Id of the display holding the window being captured, when that isn't
+    // the configured display
+    window_display: std::sync::Mutex<Option<u32>>,
+    pixel_tolerance: u8,
+    direction: ScrollDirection,
+    wheel_amount: Option<u32>,
+    dry_run: bool,
+    seam_blend: bool,
+    stall_limit: usize,
+    min_scrolls: usize,
+    exact_frames: Option<usize>,
+    home_first: bool,
+    adaptive_delay: Option<u64>,
+    hide_cursor: bool,
+    max_duration: Option<Duration>,
+    fallback_key: Option<ScrollInput>,
+    compare_width_fraction: f32,
+    metric: SimilarityMetric,
+    overlap_percent: Option<f32>,
+    stream_output: Option<std::path::PathBuf>,
+    window_title: Option<String>,
+    dump_frames: Option<String>,
+    animation: Option<(String, u32)>,
+    pause_flag: Option<std::sync::Arc<std::sync::Mutex<bool>>>,
+    stop_flag: Option<std::sync::Arc<std::sync::Mutex<bool>>>,
+    last_metadata: std::sync::Mutex<Option<CaptureMetadata>>,
+    last_stats: std::sync::Mutex<Option<CaptureStats>>,
+    frame_observer: Option<FrameObserver>,
+    progress_observer: Option<ProgressObserver>,
+}
+
+impl ScreenCapture {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(target_os = "macos")]
+            display_id: unsafe { CGMainDisplayID() },
+            #[cfg(target_os = "windows")]
+            _phantom: (),
+            display_index: 0,
+            trim_first_top: 0,
+            sticky_agreement: None,
+            grayscale: false,
+            excluded_bands: ExcludedBands::default(),
+            end_on_content_only: false,
+            trace_cursor: false,
+            cursor_trace: std::sync::Mutex::new(Vec::new()),
+            adaptive_threshold: false,
+            diff_threshold: None,
+            strict_crop: false,
+            auto_overlap: false,
+            overlap_cache: std::sync::Mutex::new(None),
+            screen_cache: std::sync::Mutex::new(None),
+            window_display: std::sync::Mutex::new(None),
+            pixel_tolerance: 0,
+            direction: ScrollDirection::Vertical,
+            wheel_amount: None,
+            dry_run: false,
+            seam_blend: false,
+            stall_limit: constants::defaults::STALL_LIMIT,
+            min_scrolls: 0,
+            exact_frames: None,
+            home_first: false,
+            adaptive_delay: None,
+            hide_cursor: false,
+            max_duration: None,
+            fallback_key: None,
+            compare_width_fraction: 1.0,
+            metric: SimilarityMetric::ExactDiff,
+            overlap_percent: None,
+            stream_output: None,
+            window_title: None,
+            dump_frames: None,
+            animation: None,
+            pause_flag: None,
+            stop_flag: None,
+            last_metadata: std::sync::Mutex::new(None),
+            last_stats: std::sync::Mutex::new(None),
+            frame_observer: None,
+            progress_observer: None,
+        }
+    }
+
+    /// Index into `screenshots::Screen::all()` of the display to capture
+    pub fn with_display(mut self, index: usize) -> Self {
+        self.display_index = index;
+        *self.screen_cache.get_mut().unwrap() = None;
+        self
+    }
+
+    /// Crops `pixels` rows (e.g. a window's title bar and toolbar) off the top of
+    /// the first frame only. Later frames have already scrolled past that band.
+    pub fn with_trim_first_top(mut self, pixels: u32) -> Self {
+        self.trim_first_top = pixels;
+        self
+    }
+
+    /// Converts every frame to grayscale right after capture, so comparison
+    /// ignores color fringing and the stitched result carries no color.
+    pub fn with_grayscale(mut self, grayscale: bool) -> Self {
+        self.grayscale = grayscale;
+        self
+    }
+
+    /// Treats consecutive frames as identical for end-of-content detection when
+    /// every differing pixel lies inside `bands`.
+    pub fn with_end_on_content_only(mut self, bands: ExcludedBands) -> Self {
+        self.excluded_bands = bands;
+        self.end_on_content_only = true;
+        self
+    }
+
+    /// Records the mouse position at every captured frame.
+    /// Retrieve the result with [`ScreenCapture::cursor_trace`] after capturing.
+    pub fn with_trace_cursor(mut self, trace_cursor: bool) -> Self {
+        self.trace_cursor = trace_cursor;
+        self
+    }
+
+    /// Ends the capture once the newly revealed band barely changes between
+    /// frames, scaling the diff threshold by how busy that band's content is.
+    pub fn with_adaptive_threshold(mut self, adaptive: bool) -> Self {
+        self.adaptive_threshold = adaptive;
+        self
+    }
+
+    /// Percentage of pixels in the newly revealed band that may differ while
+    /// frames still count as the same. A higher threshold tolerates more
+    /// motion (animations, video) before frames are called "different", so
+    /// the capture ends sooner. Setting one enables similarity-based end
+    /// detection; other options that enable it use `DIFF_THRESHOLD_PERCENTAGE`.
+    pub fn with_diff_threshold(mut self, pct: Option<f32>) -> Self {
+        self.diff_threshold = pct;
+        self
+    }
+
+    fn diff_threshold(&self) -> f32 {
+        self.diff_threshold
+            .unwrap_or(similarity::DIFF_THRESHOLD_PERCENTAGE)
+    }
+
+    /// Fails the capture when the crop region doesn't fit the screen instead
+    /// of falling back to a full screen capture.
+    pub fn with_strict_crop(mut self, strict: bool) -> Self {
+        self.strict_crop = strict;
+        self
+    }
+
+    /// Same as [`Self::with_auto_overlap`], for the `--smart-overlap` flag
+    pub fn with_smart_overlap(self, smart: bool) -> Self {
+        self.with_auto_overlap(smart)
+    }
+
+    /// Detects every pair's overlap within the first half of the frame,
+    /// reusing the previous pair's result when it still matches. The fixed
+    /// overlap stays as the fallback when no match is found.
+    pub fn with_auto_overlap(mut self, auto: bool) -> Self {
+        self.auto_overlap = auto;
+        self
+    }
+
+    /// Treats pixels as equal when each R/G/B channel differs by at most
+    /// `tolerance`, so antialiasing or rendering noise doesn't block end
+    /// detection. A non-zero tolerance enables similarity-based end detection.
+    pub fn with_pixel_tolerance(mut self, tolerance: u8) -> Self {
+        self.pixel_tolerance = tolerance;
+        self
+    }
+
+    pub fn with_direction(mut self, direction: ScrollDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Wheel clicks per scroll for the "wheel" key. `None` derives the amount
+    /// from the frame size and overlap.
+    pub fn with_wheel_amount(mut self, clicks: Option<u32>) -> Self {
+        self.wheel_amount = clicks;
+        self
+    }
+
+    /// Runs the scroll loop and end detection but skips stitching, logging
+    /// per-pair stats instead. The returned image is empty.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Cross-fade across the whole overlap instead of cutting at its middle
+    pub fn with_seam_blend(mut self, seam_blend: bool) -> Self {
+        self.seam_blend = seam_blend;
+        self
+    }
+
+    /// Detects a fixed header/footer that repeats in every frame and keeps only
+    /// one copy of each. `agreement` is the fraction (0.0-1.0) of consecutive
+    /// frame pairs in which a row must stay unchanged to count as sticky;
+    /// `None` disables the trim.
+    pub fn with_trim_sticky(mut self, agreement: Option<f32>) -> Self {
+        self.sticky_agreement = agreement;
+        self
+    }
+
+    /// Saves every captured frame into `dir` as frame_0001.png, frame_0002.png,
+    /// ... so stitching problems can be reproduced from the raw frames.
+    pub fn with_dump_frames(mut self, dir: Option<String>) -> Self {
+        self.dump_frames = dir;
+        self
+    }
+
+    /// Also saves the raw scroll sequence (before stitching) to `path` as an
+    /// animation at `fps` frames per second; see `save_animation`
+    pub fn with_animation(mut self, path: Option<String>, fps: u32) -> Self {
+        self.animation = path.map(|path| (path, fps));
+        self
+    }
+
+    /// While the flag is set the scroll loop waits without scrolling or
+    /// capturing; frames captured so far are kept and stitching continues
+    /// once it is cleared.
+    pub fn with_pause_flag(mut self, pause_flag: std::sync::Arc<std::sync::Mutex<bool>>) -> Self {
+        self.pause_flag = Some(pause_flag);
+        self
+    }
+
+    /// Setting the flag ends the scroll or timelapse loop after the current
+    /// frame and stitches what was captured, like the GUI's Stop button.
+    /// `capture_with_scroll_with_stop` takes its own flag instead.
+    pub fn with_stop_flag(mut self, stop_flag: std::sync::Arc<std::sync::Mutex<bool>>) -> Self {
+        self.stop_flag = Some(stop_flag);
+        self
+    }
+
+    /// Consecutive scrolls without visible movement before the capture ends
+    /// (0 disables stall detection)
+    pub fn with_stall_limit(mut self, stall_limit: usize) -> Self {
+        self.stall_limit = stall_limit;
+        self
+    }
+
+    /// Scrolls the target back to the top before the first capture, so a
+    /// capture started mid-page still includes everything above
+    pub fn with_home_first(mut self, home_first: bool) -> Self {
+        self.home_first = home_first;
+        self
+    }
+
+    /// Replaces the fixed scroll delay with polling until the screen stops
+    /// changing, waiting at most `max_settle_ms` per scroll
+    pub fn with_adaptive_delay(mut self, max_settle_ms: Option<u64>) -> Self {
+        self.adaptive_delay = max_settle_ms;
+        self
+    }
+
+    /// Limits end-of-content similarity checks to the centered `fraction`
+    /// of the frame width (1.0 compares everything). Much faster on wide
+    /// captures, and ignores animated chrome at the sides.
+    pub fn with_compare_strip(mut self, fraction: f32) -> Self {
+        self.compare_width_fraction = fraction;
+        self
+    }
+
+    /// Overlap as a percentage of the frame length along the scroll axis,
+    /// replacing the pixel overlap once the first frame's size is known
+    pub fn with_overlap_percent(mut self, percent: Option<f32>) -> Self {
+        self.overlap_percent = percent;
+        self
+    }
+
+    /// Writes scroll captures straight to this PNG as frames are stitched
+    /// instead of building the whole page in memory. Vertical only; the
+    /// returned image is empty and sticky trimming is skipped.
+    pub fn with_stream_output(mut self, path: Option<std::path::PathBuf>) -> Self {
+        self.stream_output = path;
+        self
+    }
+
+    /// How frames are compared for end detection and overlap scoring.
+    /// [`SimilarityMetric::Tolerance`] sets the pixel tolerance like
+    /// [`Self::with_pixel_tolerance`] and then compares as `ExactDiff`.
+    pub fn with_metric(mut self, metric: SimilarityMetric) -> Self {
+        self.metric = match metric {
+            SimilarityMetric::Tolerance(tolerance) => {
+                self.pixel_tolerance = tolerance;
+                SimilarityMetric::ExactDiff
+            }
+            metric => metric,
+        };
+        self
+    }
+
+    /// Key (or sequence, as for `--key`) pressed once when a scroll doesn't
+    /// move the content; the capture only ends if it doesn't either
+    pub fn with_fallback_key(mut self, fallback_key: Option<ScrollInput>) -> Self {
+        self.fallback_key = fallback_key;
+        self
+    }
+
+    /// Wall-clock limit for the scroll and timelapse loops, counted from the
+    /// first frame. When it runs out the frames so far are stitched, as if
+    /// the end of the content had been reached.
+    pub fn with_max_duration(mut self, max_duration: Option<Duration>) -> Self {
+        self.max_duration = max_duration;
+        self
+    }
+
+    /// Whether `with_max_duration` has run out since `start`, logging it if so
+    fn time_limit_reached(
+        &self,
+        start: std::time::Instant,
+        logs: &Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>,
+    ) -> bool {
+        let Some(max_duration) = self.max_duration else {
+            return false;
+        };
+        if start.elapsed() < max_duration {
+            return false;
+        }
+
+        Self::log_event(
+            logs,
+            &format!(
+                "Reached the time limit ({}s), stitching the frames captured so far",
+                max_duration.as_secs_f32()
+            ),
+            LogEvent::Stopped {
+                reason: StopReason::TimeLimit,
+            },
+        );
+        true
+    }
+
+    /// Keeps the mouse cursor out of the frames: hidden on macOS, parked in
+    /// the bottom-right corner of the desktop on Windows and put back after
+    /// each grab. X11 captures never include the cursor.
+    pub fn with_hide_cursor(mut self, hide_cursor: bool) -> Self {
+        self.hide_cursor = hide_cursor;
+        self
+    }
+
+    /// Crops to the first window whose title (or application name on macOS)
+    /// contains `title`, case-insensitively. Takes precedence over
+    /// `window_only`; a manual crop still wins.
+    pub fn with_window_title(mut self, title: Option<String>) -> Self {
+        self.window_title = title;
+        self
+    }
+
+    /// Scrolls to perform before end-of-content detection may stop the
+    /// capture, for pages that briefly render identical loading states
+    pub fn with_min_scrolls(mut self, min_scrolls: usize) -> Self {
+        self.min_scrolls = min_scrolls;
+        self
+    }
+
+    /// Captures and stitches exactly this many frames: end-of-content
+    /// detection is off and frames that didn't move are stitched too.
+    /// Overrides the max/min scroll options; a stop request or the time
+    /// limit still end the capture early.
+    pub fn with_exact_frames(mut self, frames: Option<usize>) -> Self {
+        self.exact_frames = frames;
+        self
+    }
+
+    /// Called with every frame captured by the scroll loop, e.g. for a live preview
+    pub fn with_frame_observer(
+        mut self,
+        observer: impl Fn(&RgbaImage) + Send + Sync + 'static,
+    ) -> Self {
+        self.frame_observer = Some(Box::new(observer));
+        self
+    }
+
+    /// Called after every scroll with the fraction (0-1) of `max_scrolls`
+    /// done; never called when the scroll count is unlimited
+    pub fn with_progress_observer(
+        mut self,
+        observer: impl Fn(f32) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress_observer = Some(Box::new(observer));
+        self
+    }
+
+    /// Time, region and scroll count of the last scroll capture
+    pub fn last_capture_metadata(&self) -> Option<CaptureMetadata> {
+        self.last_metadata.lock().unwrap().clone()
+    }
+
+    /// Overlap and difference statistics of the last scroll capture
+    pub fn last_capture_stats(&self) -> Option<CaptureStats> {
+        self.last_stats.lock().unwrap().clone()
+    }
+
+    /// Cursor positions from the last capture, in stitched image coordinates
+    pub fn cursor_trace(&self) -> Vec<(i32, i32)> {
+        self.cursor_trace.lock().unwrap().clone()
+    }
+
+    pub fn parse_crop_region(crop_str: &str) -> Option<(i32, i32, i32, i32)> {
+        let parts: Vec<i32> = crop_str
+            .split([',', ':', ' '])
+            .filter_map(|s| s.trim().parse().ok())
+            .collect();
+
+        // A zero size parses, and is rejected as an empty frame on capture
+        if parts.len() == 4 && parts[2] >= 0 && parts[3] >= 0 {
+            Some((parts[0], parts[1], parts[2], parts[3]))
+        } else {
+            None
+        }
+    }
+
+    /// Runs an AppleScript, killing osascript if it doesn't finish in time.
+    /// System Events can hang indefinitely while Accessibility permission is
+    /// only partially granted, which would otherwise freeze the capture.
+    #[cfg(target_os = "macos")]
+    fn run_osascript(script: &str) -> Result<std::process::Output> {
+        let mut child = std::process::Command::new("osascript")
+            .arg("-e")
+            .arg(script)
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()?;
+
+        let deadline =
+            std::time::Instant::now() + Duration::from_millis(timing::OSASCRIPT_TIMEOUT_MS);
+        while child.try_wait()?.is_none() {
+            if std::time::Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(anyhow::anyhow!(
+                    "osascript did not respond within {}s. Check System Settings > Privacy & Security > Accessibility: remove and re-add your terminal (or this app), then try again",
+                    timing::OSASCRIPT_TIMEOUT_MS / 1000
+                ));
+            }
+            thread::sleep(Duration::from_millis(timing::OSASCRIPT_POLL_MS));
+        }
+
+        Ok(child.wait_with_output()?)
+    }
+
+    #[cfg(target_os = "macos")]
+    fn get_mouse_position() -> Result<(i32, i32)> {
+        let script = r#"
+tell application "System Events"
+    set mousePos to position of mouse
+    return (item 1 of mousePos) & "," & (item 2 of mousePos)
+end tell
+"#;
+
+        let output = Self::run_osascript(script)?;
+
+        if output.status.success() {
+            let result = String::from_utf8_lossy(&output.stdout);
+            let coords: Vec<i32> = result
+                .trim()
+                .split(',')
+                .filter_map(|s| s.trim().parse().ok())
+                .collect();
+
+            if coords.len() == 2 {
+                return Ok((coords[0], coords[1]));
+            }
+        }
+
+        Err(anyhow::anyhow!("Failed to get mouse position"))
+    }
+
+    #[cfg(target_os = "windows")]
+    fn get_mouse_position() -> Result<(i32, i32)> {
+        unsafe {
+            let mut point = POINT { x: 0, y: 0 };
+            if GetCursorPos(&mut point).is_ok() {
+                Ok((point.x, point.y))
+            } else {
+                Err(anyhow::anyhow!("Failed to get cursor position"))
+            }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn get_mouse_position() -> Result<(i32, i32)> {
+        let (conn, root) = Self::x11_connect()?;
+        let pointer = conn.query_pointer(root)?.reply()?;
+        Ok((pointer.root_x as i32, pointer.root_y as i32))
+    }
+
+    #[cfg(target_os = "macos")]
+    fn enable_zoom() -> Result<()> {
+        let script = r#"
+tell application "System Events"
+    key code 28 using {command down, option down}
+end tell
+"#;
+        Self::run_osascript(script)?;
+        Ok(())
+    }
+
+    #[cfg(target_os = "windows")]
+    fn enable_zoom() -> Result<()> {
+        // Launch Windows Magnifier
+        let _ = std::process::Command::new("magnify.exe").spawn();
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    fn enable_zoom() -> Result<()> {
+        // Turn on the GNOME screen magnifier; other desktops simply ignore this
+        let _ = std::process::Command::new("gsettings")
+            .args([
+                "set",
+                "org.gnome.desktop.a11y.applications",
+                "screen-magnifier-enabled",
+                "true",
+            ])
+            .output();
+        Ok(())
+    }
+
+    /// Polls the mouse position until Enter is pressed. Positions stay in
+    /// the logical units `--crop` takes; on a scaled (Retina) display the
+    /// physical pixels they capture, at `scale`, are shown alongside.
+    fn show_live_coordinates(scale: f64) -> Result<(i32, i32)> {
+        use std::io::{self, Write};
+
+        println!("   Live coordinates (move mouse, press ENTER to select):");
+        println!("   ┌─────────────────────────────────────────┐");
+
+        // Show live coordinates until Enter is pressed
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        std::thread::spawn(move || {
+            let mut input = String::new();
+            let _ = io::stdin().read_line(&mut input);
+            let _ = tx.send(());
+        });
+
+        loop {
+            // Bail out instead of polling forever if the helper keeps failing
+            // (e.g. osascript timing out without Accessibility permission)
+            let (x, y) = Self::get_mouse_position().inspect_err(|_| println!())?;
+            if scale == 1.0 {
+                print!("\r   │ Current position: ({:4}, {:4})          │", x, y);
+            } else {
+                let (px, py, _, _) = scale_crop_region((x, y, 0, 0), scale);
+                print!(
+                    "\r   │ Current position: ({:4}, {:4}) pt = ({:5}, {:5}) px │",
+                    x, y, px, py
+                );
+            }
+            io::stdout().flush()?;
+
+            // Check if Enter was pressed
+            if rx.try_recv().is_ok() {
+                let (x, y) = Self::get_mouse_position()?;
+                println!("\r   └─────────────────────────────────────────┘");
+                return Ok((x, y));
+            }
+
+            thread::sleep(Duration::from_millis(timing::MOUSE_POSITION_POLL_MS));
+        }
+    }
+
+    /// Lets the user pick a capture region with the mouse (or text prompts
+    /// without a display)
+    pub fn select_region_interactive(
+        snap_to_window: bool,
+    ) -> std::result::Result<(i32, i32, i32, i32), CaptureError> {
+        Ok(Self::select_region_interactive_impl(snap_to_window)?)
+    }
+
+    fn select_region_interactive_impl(snap_to_window: bool) -> Result<(i32, i32, i32, i32)> {
+        use std::io::{self, Write};
+
+        println!("\nINTERACTIVE REGION SELECTION");
+        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!();
+
+        #[cfg(target_os = "macos")]
+        {
+            println!("TIP: Press Option+Command+8 to toggle macOS Zoom (magnifier)");
+            println!("     Option+Command+= to zoom in, Option+Command+- to zoom out");
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            println!("TIP: Press Win+Plus to open Windows Magnifier");
+            println!("     Win+Plus/Minus to zoom in/out");
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            println!("TIP: Press Super+Alt+8 to toggle the GNOME magnifier");
+            println!("     Super+Alt+=/- to zoom in/out");
+        }
+
+        println!();
+
+        // Offer to enable zoom automatically
+        print!("Do you want to enable Magnifier now? (y/N): ");
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        if input.trim().to_lowercase() == "y" {
+            println!("Enabling Magnifier...");
+            Self::enable_zoom()?;
+            thread::sleep(Duration::from_millis(timing::ZOOM_ENABLE_DELAY_MS));
+        }
+
+        // Detected once; positions are in points on Retina displays while
+        // frames are captured in pixels
+        let scale = Self::new().display_scale_factor();
+        if scale != 1.0 {
+            println!(
+                "Display scale {:.1}x: --crop takes points, captured in pixels (shown as px)",
+                scale
+            );
+        }
+
+        // Corners close to the focused window's edges land exactly on them
+        let window = if snap_to_window {
+            let window = Self::new().get_focused_window_bounds().ok().flatten();
+            match window {
+                Some((x, y, width, height)) => println!(
+                    "Snapping corners within {}pt to the focused window ({}x{} at ({}, {}))",
+                    display::SNAP_DISTANCE,
+                    width,
+                    height,
+                    x,
+                    y
+                ),
+                None => println!("No focused window found, corners won't snap"),
+            }
+            window
+        } else {
+            None
+        };
+        let snap = |corner: (i32, i32)| match window {
+            Some(window) => {
+                let (snapped, moved) = snap_to_window_edges(corner, window, display::SNAP_DISTANCE);
+                if moved {
+                    println!(
+                        "Snapped ({}, {}) to the window edge at ({}, {})",
+                        corner.0, corner.1, snapped.0, snapped.1
+                    );
+                }
+                snapped
+            }
+            None => corner,
+        };
+
+        println!();
+        println!("Step 1/2: Position mouse at TOP-LEFT corner");
+
+        let (x1, y1) = snap(Self::show_live_coordinates(scale)?);
+        println!("Top-left corner: ({}, {})", x1, y1);
+        println!();
+
+        println!("Step 2/2: Position mouse at BOTTOM-RIGHT corner");
+
+        let (x2, y2) = snap(Self::show_live_coordinates(scale)?);
+        println!("Bottom-right corner: ({}, {})", x2, y2);
+        println!();
+
+        // Calculate region
+        let x = x1.min(x2);
+        let y = y1.min(y2);
+        let width = (x2 - x1).abs();
+        let height = (y2 - y1).abs();
+
+        if width <= 0 || height <= 0 {
+            return Err(anyhow::anyhow!(
+                "Invalid region: width and height must be positive"
+            ));
+        }
+
+        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("Region selected:");
+        println!("   Position: ({}, {})", x, y);
+        println!("   Size: {}x{}", width, height);
+        if scale != 1.0 {
+            let (px, py, pw, ph) = scale_crop_region((x, y, width, height), scale);
+            println!("   Captured as: {}x{} pixels at ({}, {})", pw, ph, px, py);
+        }
+        println!();
+        println!("Use this command:");
+        println!("   --crop \"{},{},{},{}\"", x, y, width, height);
+        println!();
+
+        Ok((x, y, width, height))
+    }
+
+    #[cfg(target_os = "macos")]
+    fn get_focused_window_bounds(&self) -> Result<Option<(i32, i32, i32, i32)>> {
+        let script = r#"
+tell application "System Events"
+    set frontApp to first application process whose frontmost is true
+    set frontWindow to front window of frontApp
+    set windowPosition to position of frontWindow
+    set windowSize to size of frontWindow
+    return (item 1 of windowPosition) & "," & (item 2 of windowPosition) & "," & (item 1 of windowSize) & "," & (item 2 of windowSize)
+end tell
+"#;
+
+        let output = Self::run_osascript(script)?;
+
+        if output.status.success() {
+            let result = String::from_utf8_lossy(&output.stdout);
+            let parts: Vec<i32> = result
+                .trim()
+                .split(',')
+                .filter_map(|s| s.trim().parse().ok())
+                .collect();
+
+            if parts.len() == 4 {
+                return Ok(Some((parts[0], parts[1], parts[2], parts[3])));
+            }
+        }
+
+        Ok(None)
+    }
+
+    #[cfg(target_os = "windows")]
+    fn get_focused_window_bounds(&self) -> Result<Option<(i32, i32, i32, i32)>> {
+        let hwnd = unsafe { GetForegroundWindow() };
+        if hwnd.0 == std::ptr::null_mut() {
+            return Ok(None);
+        }
+        Ok(Self::window_rect(hwnd))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn x11_connect() -> Result<(RustConnection, Window)> {
+        let (conn, screen_num) = x11rb::connect(None)
+            .map_err(|e| anyhow::anyhow!("Failed to connect to X server: {}", e))?;
+        let root = conn.setup().roots[screen_num].root;
+        Ok((conn, root))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn x11_atom(conn: &RustConnection, name: &str) -> Result<Atom> {
+        Ok(conn.intern_atom(false, name.as_bytes())?.reply()?.atom)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn x11_active_window(conn: &RustConnection, root: Window) -> Result<Option<Window>> {
+        let net_active_window = Self::x11_atom(conn, "_NET_ACTIVE_WINDOW")?;
+        let reply = conn
+            .get_property(false, root, net_active_window, AtomEnum::WINDOW, 0, 1)?
+            .reply()?;
+
+        Ok(reply
+            .value32()
+            .and_then(|mut values| values.next())
+            .filter(|&window| window != 0))
+    }
+
+    /// Window geometry in root coordinates. GetGeometry alone is relative to
+    /// the parent, which is the window manager's frame on reparenting WMs.
+    #[cfg(target_os = "linux")]
+    fn x11_window_bounds(
+        conn: &RustConnection,
+        root: Window,
+        window: Window,
+    ) -> Result<Option<(i32, i32, i32, i32)>> {
+        let geometry = conn.get_geometry(window)?.reply()?;
+        let origin = conn.translate_coordinates(window, root, 0, 0)?.reply()?;
+
+        if geometry.width == 0 || geometry.height == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some((
+            origin.dst_x as i32,
+            origin.dst_y as i32,
+            geometry.width as i32,
+            geometry.height as i32,
+        )))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn x11_window_title(conn: &RustConnection, window: Window) -> Result<String> {
+        let net_wm_name = Self::x11_atom(conn, "_NET_WM_NAME")?;
+        let utf8_string = Self::x11_atom(conn, "UTF8_STRING")?;
+
+        let reply = conn
+            .get_property(false, window, net_wm_name, utf8_string, 0, u32::MAX)?
+            .reply()?;
+        if !reply.value.is_empty() {
+            return Ok(String::from_utf8_lossy(&reply.value).into_owned());
+        }
+
+        // Fall back to the legacy Latin-1 title
+        let reply = conn
+            .get_property(
+                false,
+                window,
+                AtomEnum::WM_NAME,
+                AtomEnum::STRING,
+                0,
+                u32::MAX,
+            )?
+            .reply()?;
+        Ok(reply.value.iter().map(|&b| b as char).collect())
+    }
+
+    /// Asks the window manager to raise and focus `window` via _NET_ACTIVE_WINDOW
+    #[cfg(target_os = "linux")]
+    fn x11_activate(conn: &RustConnection, root: Window, window: Window) -> Result<()> {
+        let net_active_window = Self::x11_atom(conn, "_NET_ACTIVE_WINDOW")?;
+        // Source indication 2 = pager, which WMs honor without focus-stealing checks
+        let event = ClientMessageEvent::new(
+            32,
+            window,
+            net_active_window,
+            [2, x11rb::CURRENT_TIME, 0, 0, 0],
+        );
+        conn.send_event(
+            false,
+            root,
+            EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+            event,
+        )?;
+        conn.flush()?;
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    fn get_focused_window_bounds(&self) -> Result<Option<(i32, i32, i32, i32)>> {
+        let (conn, root) = Self::x11_connect()?;
+        match Self::x11_active_window(&conn, root)? {
+            Some(window) => Self::x11_window_bounds(&conn, root, window),
+            None => Ok(None),
+        }
+    }
+
+    /// Bounds of the first window whose title (or application name on macOS)
+    /// contains `title`, case-insensitively, without raising it
+    pub fn get_window_bounds_by_title(
+        &self,
+        title: &str,
+    ) -> std::result::Result<Option<(i32, i32, i32, i32)>, CaptureError> {
+        Ok(self.get_window_bounds_by_title_impl(title)?)
+    }
+
+    /// Brings the first window whose title contains `title` (case-insensitive)
+    /// to the front and returns its bounds, or `None` if no window matches.
+    pub fn activate_window_by_title(
+        &self,
+        title: &str,
+    ) -> std::result::Result<Option<(i32, i32, i32, i32)>, CaptureError> {
+        Ok(self.activate_window_by_title_impl(title)?)
+    }
+
+    /// Bounds of the frontmost window of the first application whose name
+    /// contains `title`, falling back to the first window whose own title
+    /// does. AppleScript's `contains` is case-insensitive.
+    #[cfg(target_os = "macos")]
+    fn get_window_bounds_by_title_impl(&self, title: &str) -> Result<Option<(i32, i32, i32, i32)>> {
+        let escaped = title.replace('\\', "\\\\").replace('"', "\\\"");
+        let script = format!(
+            r#"
+tell application "System Events"
+    set matchingProcs to application processes whose visible is true and name contains "{0}"
+    repeat with proc in matchingProcs
+        if (count of windows of proc) > 0 then
+            set win to front window of proc
+            set windowPosition to position of win
+            set windowSize to size of win
+            return (item 1 of windowPosition) & "," & (item 2 of windowPosition) & "," & (item 1 of windowSize) & "," & (item 2 of windowSize)
+        end if
+    end repeat
+    repeat with proc in (application processes whose visible is true)
+        repeat with win in windows of proc
+            if (name of win as text) contains "{0}" then
+                set windowPosition to position of win
+                set windowSize to size of win
+                return (item 1 of windowPosition) & "," & (item 2 of windowPosition) & "," & (item 1 of windowSize) & "," & (item 2 of windowSize)
+            end if
+        end repeat
+    end repeat
+end tell
+return ""
+"#,
+            escaped
+        );
+
+        let output = Self::run_osascript(&script)?;
+
+        if output.status.success() {
+            let result = String::from_utf8_lossy(&output.stdout);
+            let parts: Vec<i32> = result
+                .trim()
+                .split(',')
+                .filter_map(|s| s.trim().parse().ok())
+                .collect();
+
+            if parts.len() == 4 {
+                return Ok(Some((parts[0], parts[1], parts[2], parts[3])));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Brings the window `get_window_bounds_by_title_impl` would pick, the
+    /// front window of an application whose name contains `title` or else
+    /// the first window whose own title does, to the front and returns its
+    /// bounds, or `None` if no window matches.
+    #[cfg(target_os = "macos")]
+    fn activate_window_by_title_impl(&self, title: &str) -> Result<Option<(i32, i32, i32, i32)>> {
+        let escaped = title.replace('\\', "\\\\").replace('"', "\\\"");
+        let script = format!(
+            r#"
+tell application "System Events"
+    set matchingProcs to application processes whose visible is true and name contains "{0}"
+    repeat with proc in matchingProcs
+        if (count of windows of proc) > 0 then
+            set win to front window of proc
+            set frontmost of proc to true
+            perform action "AXRaise" of win
+            set windowPosition to position of win
+            set windowSize to size of win
+            return (item 1 of windowPosition) & "," & (item 2 of windowPosition) & "," & (item 1 of windowSize) & "," & (item 2 of windowSize)
+        end if
+    end repeat
+    repeat with proc in (application processes whose visible is true)
+        repeat with win in windows of proc
+            if (name of win as text) contains "{0}" then
+                set frontmost of proc to true
+                perform action "AXRaise" of win
+                set windowPosition to position of win
+                set windowSize to size of win
+                return (item 1 of windowPosition) & "," & (item 2 of windowPosition) & "," & (item 1 of windowSize) & "," & (item 2 of windowSize)
+            end if
+        end repeat
+    end repeat
+end tell
+return ""
+"#,
+            escaped
+        );
+
+        let output = Self::run_osascript(&script)?;
+
+        if output.status.success() {
+            let result = String::from_utf8_lossy(&output.stdout);
+            let parts: Vec<i32> = result
+                .trim()
+                .split(',')
+                .filter_map(|s| s.trim().parse().ok())
+                .collect();
+
+            if parts.len() == 4 {
+                return Ok(Some((parts[0], parts[1], parts[2], parts[3])));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// `(x, y, width, height)` of `hwnd` in physical pixels, matching the
+    /// captured frames. Without per-monitor DPI awareness (see
+    /// [`enable_dpi_awareness`]) Windows reports virtualized logical
+    /// coordinates, which are scaled up by the window's DPI here.
+    #[cfg(target_os = "windows")]
+    fn window_rect(hwnd: HWND) -> Option<(i32, i32, i32, i32)> {
+        unsafe {
+            let mut rect = RECT::default();
+            GetWindowRect(hwnd, &mut rect).ok()?;
+            let bounds = (
+                rect.left,
+                rect.top,
+                rect.right - rect.left,
+                rect.bottom - rect.top,
+            );
+
+            let awareness = GetAwarenessFromDpiAwarenessContext(GetThreadDpiAwarenessContext());
+            let dpi = GetDpiForWindow(hwnd);
+            if awareness == DPI_AWARENESS_UNAWARE && dpi > 0 {
+                Some(scale_rect_for_dpi(bounds, dpi))
+            } else {
+                Some(bounds)
+            }
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    fn find_window_by_title(title: &str) -> Option<HWND> {
+        struct Search {
+            needle: String,
+            found: Option<HWND>,
+        }
+
+        unsafe extern "system" fn enum_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
+            let search = unsafe { &mut *(lparam.0 as *mut Search) };
+            if !unsafe { IsWindowVisible(hwnd) }.as_bool() {
+                return BOOL(1);
+            }
+
+            let mut buffer = [0u16; 512];
+            let len = unsafe { GetWindowTextW(hwnd, &mut buffer) };
+            if len > 0 {
+                let window_title = String::from_utf16_lossy(&buffer[..len as usize]);
+                if window_title.to_lowercase().contains(&search.needle) {
+                    search.found = Some(hwnd);
+                    return BOOL(0); // Stop enumerating
+                }
+            }
+            BOOL(1)
+        }
+
+        let mut search = Search {
+            needle: title.to_lowercase(),
+            found: None,
+        };
+        unsafe {
+            // Returns an error when the callback stops enumeration early
+            let _ = EnumWindows(Some(enum_proc), LPARAM(&mut search as *mut Search as isize));
+        }
+        search.found
+    }
+
+    /// Bounds of the window titled exactly `title`, or else of the first
+    /// visible window whose title contains it (case-insensitive). The window
+    /// is not raised.
+    #[cfg(target_os = "windows")]
+    fn get_window_bounds_by_title_impl(&self, title: &str) -> Result<Option<(i32, i32, i32, i32)>> {
+        let wide: Vec<u16> = title.encode_utf16().chain(std::iter::once(0)).collect();
+        let exact = unsafe { FindWindowW(PCWSTR::null(), PCWSTR(wide.as_ptr())) }
+            .ok()
+            .filter(|hwnd| hwnd.0 != std::ptr::null_mut());
+        let Some(hwnd) = exact.or_else(|| Self::find_window_by_title(title)) else {
+            return Ok(None);
+        };
+
+        Ok(Self::window_rect(hwnd))
+    }
+
+    /// Brings the first window whose title contains `title` (case-insensitive)
+    /// to the front and returns its bounds, or `None` if no window matches.
+    #[cfg(target_os = "windows")]
+    fn activate_window_by_title_impl(&self, title: &str) -> Result<Option<(i32, i32, i32, i32)>> {
+        let Some(hwnd) = Self::find_window_by_title(title) else {
+            return Ok(None);
+        };
+
+        unsafe {
+            if IsIconic(hwnd).as_bool() {
+                let _ = ShowWindow(hwnd, SW_RESTORE);
+            }
+            let _ = SetForegroundWindow(hwnd);
+        }
+        Ok(Self::window_rect(hwnd))
+    }
+
+    /// Brings the first window whose title contains `title` (case-insensitive)
+    /// to the front and returns its bounds, or `None` if no window matches.
+    #[cfg(target_os = "linux")]
+    fn activate_window_by_title_impl(&self, title: &str) -> Result<Option<(i32, i32, i32, i32)>> {
+        let (conn, root) = Self::x11_connect()?;
+        match Self::x11_find_window_by_title(&conn, root, title)? {
+            Some(window) => {
+                Self::x11_activate(&conn, root, window)?;
+                Self::x11_window_bounds(&conn, root, window)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Bounds of the first window whose title contains `title`
+    /// (case-insensitive). The window is not raised.
+    #[cfg(target_os = "linux")]
+    fn get_window_bounds_by_title_impl(&self, title: &str) -> Result<Option<(i32, i32, i32, i32)>> {
+        let (conn, root) = Self::x11_connect()?;
+        match Self::x11_find_window_by_title(&conn, root, title)? {
+            Some(window) => Self::x11_window_bounds(&conn, root, window),
+            None => Ok(None),
+        }
+    }
+
+    /// First client window in _NET_CLIENT_LIST whose title contains `title`
+    #[cfg(target_os = "linux")]
+    fn x11_find_window_by_title(
+        conn: &RustConnection,
+        root: Window,
+        title: &str,
+    ) -> Result<Option<Window>> {
+        let net_client_list = Self::x11_atom(conn, "_NET_CLIENT_LIST")?;
+        let reply = conn
+            .get_property(false, root, net_client_list, AtomEnum::WINDOW, 0, u32::MAX)?
+            .reply()?;
+        let windows: Vec<Window> = reply.value32().map(|v| v.collect()).unwrap_or_default();
+
+        let needle = title.to_lowercase();
+        for window in windows {
+            let window_title = Self::x11_window_title(conn, window)?;
+            if window_title.to_lowercase().contains(&needle) {
+                return Ok(Some(window));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Remembers which window currently has focus so it can be restored later
+    #[cfg(target_os = "macos")]
+    pub fn focused_window(&self) -> Option<FocusedWindow> {
+        let script = r#"
+tell application "System Events"
+    return name of first application process whose frontmost is true
+end tell
+"#;
+
+        let output = Self::run_osascript(script).ok()?;
+
+        let app_name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if output.status.success() && !app_name.is_empty() {
+            Some(FocusedWindow { app_name })
+        } else {
+            None
+        }
+    }
+
+    /// Remembers which window currently has focus so it can be restored later
+    #[cfg(target_os = "windows")]
+    pub fn focused_window(&self) -> Option<FocusedWindow> {
+        let hwnd = unsafe { GetForegroundWindow() };
+        if hwnd.0.is_null() {
+            None
+        } else {
+            Some(FocusedWindow {
+                hwnd: hwnd.0 as isize,
+            })
+        }
+    }
+
+    /// Remembers which window currently has focus so it can be restored later
+    #[cfg(target_os = "linux")]
+    pub fn focused_window(&self) -> Option<FocusedWindow> {
+        let (conn, root) = Self::x11_connect().ok()?;
+        let window = Self::x11_active_window(&conn, root).ok()??;
+        Some(FocusedWindow { window })
+    }
+
+    /// Gives focus back to a window remembered by `focused_window`
+    pub fn restore_focus(&self, window: &FocusedWindow) -> std::result::Result<(), CaptureError> {
+        Ok(self.restore_focus_impl(window)?)
+    }
+
+    #[cfg(target_os = "macos")]
+    fn restore_focus_impl(&self, window: &FocusedWindow) -> Result<()> {
+        let script = format!(
+            r#"
+tell application "System Events"
+    set frontmost of process "{}" to true
+end tell
+"#,
+            window.app_name.replace('"', "\\\"")
+        );
+        Self::run_osascript(&script)?;
+        Ok(())
+    }
+
+    #[cfg(target_os = "windows")]
+    fn restore_focus_impl(&self, window: &FocusedWindow) -> Result<()> {
+        unsafe {
+            let _ = SetForegroundWindow(HWND(window.hwnd as *mut _));
+        }
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    fn restore_focus_impl(&self, window: &FocusedWindow) -> Result<()> {
+        let (conn, root) = Self::x11_connect()?;
+        Self::x11_activate(&conn, root, window.window)
+    }
+
+    /// One line per connected display, e.g. "0: 1920x1080 at (0, 0) (primary)"
+    pub fn display_descriptions() -> std::result::Result<Vec<String>, CaptureError> {
+        let screens = Self::all_screens()?;
+
+        Ok(screens
+            .iter()
+            .enumerate()
+            .map(|(i, screen)| {
+                let info = &screen.display_info;
+                format!(
+                    "{}: {}x{} at ({}, {}){}",
+                    i,
+                    info.width,
+                    info.height,
+                    info.x,
+                    info.y,
+                    if info.is_primary { " (primary)" } else { "" }
+                )
+            })
+            .collect())
+    }
+
+    /// Lists the connected screens, retrying a few times because the list can
+    /// come back empty while the screen is locked or a display is waking up
+    fn all_screens() -> Result<Vec<screenshots::Screen>> {
+        let mut last_error = None;
+
+        for attempt in 1..=timing::SCREEN_RETRY_ATTEMPTS {
+            match screenshots::Screen::all() {
+                Ok(screens) if !screens.is_empty() => return Ok(screens),
+                Ok(_) => last_error = Some("no screens were reported".to_string()),
+                Err(e) => last_error = Some(e.to_string()),
+            }
+
+            if attempt < timing::SCREEN_RETRY_ATTEMPTS {
+                debug_line(
+                    1,
+                    &format!(
+                        "Screen list unavailable (attempt {}/{}), retrying...",
+                        attempt,
+                        timing::SCREEN_RETRY_ATTEMPTS
+                    ),
+                );
+                thread::sleep(Duration::from_millis(timing::SCREEN_RETRY_DELAY_MS));
+            }
+        }
+
+        Err(CaptureError::NoScreen {
+            cause: format!(
+                "{} after {} attempts",
+                last_error.unwrap_or_default(),
+                timing::SCREEN_RETRY_ATTEMPTS
+            ),
+        }
+        .into())
+    }
+
+    #[cfg(target_os = "macos")]
+    fn no_screen_hint() -> &'static str {
+        "Check that this app has Screen Recording permission in System Settings > Privacy & Security, and that the screen is unlocked."
+    }
+
+    #[cfg(target_os = "linux")]
+    fn no_screen_hint() -> &'static str {
+        "Check that a display is connected and DISPLAY points to a running X server (headless sessions need e.g. Xvfb)."
+    }
+
+    #[cfg(target_os = "windows")]
+    fn no_screen_hint() -> &'static str {
+        "Check that a display is connected and the session is not locked or disconnected (e.g. a minimized Remote Desktop window)."
+    }
+
+    /// The display to capture, enumerated once and then served from the cache
+    /// until [`Self::forget_screen`] drops it
+    fn screen(&self) -> Result<screenshots::Screen> {
+        if let Some(screen) = *self.screen_cache.lock().unwrap() {
+            return Ok(screen);
+        }
+
+        let screens = Self::all_screens()?;
+
+        let window_display = *self.window_display.lock().unwrap();
+        if let Some(id) = window_display
+            && let Some(screen) = screens.iter().find(|s| s.display_info.id == id)
+        {
+            *self.screen_cache.lock().unwrap() = Some(*screen);
+            return Ok(*screen);
+        }
+
+        let count = screens.len();
+        let screen = screens.into_iter().nth(self.display_index).ok_or_else(|| {
+            let available = Self::display_descriptions().unwrap_or_default();
+            anyhow::anyhow!(
+                "Display {} not found ({} available):\n  {}",
+                self.display_index,
+                count,
+                available.join("\n  ")
+            )
+        })?;
+
+        *self.screen_cache.lock().unwrap() = Some(screen);
+        Ok(screen)
+    }
+
+    /// Switches capture to the display containing the center of `bounds`, a
+    /// window in global coordinates (which go negative for monitors left of
+    /// or above the primary one), and returns that display's info so the
+    /// bounds can be made relative to it. Keeps the current display when no
+    /// display contains the window.
+    fn follow_window(
+        &self,
+        bounds: (i32, i32, i32, i32),
+        logs: &Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>,
+    ) -> Result<screenshots::display_info::DisplayInfo> {
+        let current = self.screen()?.display_info;
+        let (x, y, w, h) = bounds;
+        let (center_x, center_y) = (x as i64 + w as i64 / 2, y as i64 + h as i64 / 2);
+        let contains = |info: &screenshots::display_info::DisplayInfo| {
+            (info.x as i64..info.x as i64 + info.width as i64).contains(&center_x)
+                && (info.y as i64..info.y as i64 + info.height as i64).contains(&center_y)
+        };
+        if contains(&current) {
+            return Ok(current);
+        }
+
+        let Some((index, screen)) = Self::all_screens()?
+            .into_iter()
+            .enumerate()
+            .find(|(_, screen)| contains(&screen.display_info))
+        else {
+            return Ok(current);
+        };
+
+        let info = screen.display_info;
+        Self::log_msg(
+            logs,
+            &format!(
+                "Window is on display {} ({}x{} at ({}, {})), capturing that display",
+                index, info.width, info.height, info.x, info.y
+            ),
+        );
+        *self.window_display.lock().unwrap() = Some(info.id);
+        *self.screen_cache.lock().unwrap() = Some(screen);
+        Ok(info)
+    }
+
+    /// Drops the cached display so the next capture enumerates screens again,
+    /// e.g. after a monitor was disconnected or rearranged
+    fn forget_screen(&self) {
+        *self.screen_cache.lock().unwrap() = None;
+    }
+
+    /// Ratio of physical pixels to logical points on the captured display.
+    /// AppleScript positions and window bounds are in points while captures
+    /// are in pixels, so crops must be scaled by this on Retina displays.
+    #[cfg(target_os = "macos")]
+    fn display_scale_factor(&self) -> f64 {
+        use core_graphics::display::CGDisplay;
+
+        let display_id = self
+            .screen()
+            .map(|screen| screen.display_info.id)
+            .unwrap_or(self.display_id);
+        let display = CGDisplay::new(display_id);
+        let logical_height = display.bounds().size.height;
+
+        if logical_height > 0.0 {
+            display.pixels_high() as f64 / logical_height
+        } else {
+            1.0
+        }
+    }
+
+    /// Other platforms already report window and cursor positions in pixels
+    #[cfg(not(target_os = "macos"))]
+    fn display_scale_factor(&self) -> f64 {
+        1.0
+    }
+
+    /// Checks that the crop region lies entirely on the screen, so a bad
+    /// region fails before capturing instead of falling back to full screen.
+    pub fn validate_crop_region(
+        &self,
+        crop: (i32, i32, i32, i32),
+    ) -> std::result::Result<(), CaptureError> {
+        let (x, y, width, height) = crop;
+        // A zero-sized crop would otherwise stitch into a 1x1 placeholder
+        if width <= 0 || height <= 0 {
+            return Err(CaptureError::EmptyFrame { crop: Some(crop) });
+        }
+
+        let info = self.screen()?.display_info;
+
+        // Crops apply to the captured bitmap, which is in physical pixels
+        let screen_width = (info.width as f32 * info.scale_factor).round() as u32;
+        let screen_height = (info.height as f32 * info.scale_factor).round() as u32;
+
+        if x < 0
+            || y < 0
+            || x as i64 + width as i64 > screen_width as i64
+            || y as i64 + height as i64 > screen_height as i64
+        {
+            return Err(CaptureError::CropOutOfBounds {
+                crop,
+                screen: (screen_width, screen_height),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn capture_screen(&self, crop_region: Option<(i32, i32, i32, i32)>) -> Result<RgbaImage> {
+        let rgba_image = if self.hide_cursor {
+            let saved = self.hide_cursor()?;
+            let result = self.capture_backend();
+            self.restore_cursor(saved);
+            result?
+        } else {
+            self.capture_backend()?
+        };
+        let (width, height) = rgba_image.dimensions();
+
+        // Apply crop if specified
+        if let Some(crop) = crop_region {
+            if let Some((crop_x, crop_y, crop_w, crop_h)) = fit_crop_region(crop, (width, height)) {
+                let cropped =
+                    image::imageops::crop_imm(&rgba_image, crop_x, crop_y, crop_w, crop_h)
+                        .to_image();
+                return Ok(self.apply_color_mode(cropped));
+            } else if self.strict_crop {
+                return Err(CaptureError::CropOutOfBounds {
+                    crop,
+                    screen: (width, height),
+                }
+                .into());
+            } else {
+                print_line("Crop region out of bounds, using full screen");
+            }
+        }
+
+        Ok(self.apply_color_mode(rgba_image))
+    }
+
+    fn capture_backend(&self) -> Result<RgbaImage> {
+        match Backend::detect() {
+            Backend::Screenshots => self.capture_with_screenshots(),
+            #[cfg(target_os = "linux")]
+            Backend::Portal => Self::capture_with_portal(),
+        }
+    }
+
+    /// Takes the cursor out of view for one grab and returns its previous
+    /// position when it had to be moved
+    #[cfg(target_os = "macos")]
+    fn hide_cursor(&self) -> Result<Option<(i32, i32)>> {
+        use core_graphics::display::CGDisplay;
+
+        let display_id = self
+            .screen()
+            .map(|screen| screen.display_info.id)
+            .unwrap_or(self.display_id);
+        CGDisplay::new(display_id)
+            .hide_cursor()
+            .map_err(|e| anyhow::anyhow!("Failed to hide cursor: error {}", e))?;
+        Ok(None)
+    }
+
+    #[cfg(target_os = "macos")]
+    fn restore_cursor(&self, _saved: Option<(i32, i32)>) {
+        use core_graphics::display::CGDisplay;
+
+        let display_id = self
+            .screen()
+            .map(|screen| screen.display_info.id)
+            .unwrap_or(self.display_id);
+        let _ = CGDisplay::new(display_id).show_cursor();
+    }
+
+    /// Moves the cursor to the last pixel of the virtual desktop, which is
+    /// outside any crop that doesn't reach the bottom-right corner, and
+    /// returns where it was
+    #[cfg(target_os = "windows")]
+    fn hide_cursor(&self) -> Result<Option<(i32, i32)>> {
+        use windows::Win32::UI::WindowsAndMessaging::{
+            GetSystemMetrics, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN, SM_XVIRTUALSCREEN,
+            SM_YVIRTUALSCREEN, SetCursorPos,
+        };
+
+        let saved = Self::get_mouse_position()?;
+        unsafe {
+            let right = GetSystemMetrics(SM_XVIRTUALSCREEN) + GetSystemMetrics(SM_CXVIRTUALSCREEN);
+            let bottom = GetSystemMetrics(SM_YVIRTUALSCREEN) + GetSystemMetrics(SM_CYVIRTUALSCREEN);
+            SetCursorPos(right - 1, bottom - 1)
+                .map_err(|e| anyhow::anyhow!("Failed to move cursor: {}", e))?;
+        }
+        // Give the compositor a moment to redraw without the cursor
+        thread::sleep(Duration::from_millis(timing::CURSOR_HIDE_MS));
+        Ok(Some(saved))
+    }
+
+    #[cfg(target_os = "windows")]
+    fn restore_cursor(&self, saved: Option<(i32, i32)>) {
+        if let Some((x, y)) = saved {
+            unsafe {
+                let _ = windows::Win32::UI::WindowsAndMessaging::SetCursorPos(x, y);
+            }
+        }
+    }
+
+    /// The X11 backend grabs the root window without the cursor sprite
+    #[cfg(target_os = "linux")]
+    fn hide_cursor(&self) -> Result<Option<(i32, i32)>> {
+        Ok(None)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn restore_cursor(&self, _saved: Option<(i32, i32)>) {}
+
+    fn capture_with_screenshots(&self) -> Result<RgbaImage> {
+        let captured_image = match self.screen()?.capture() {
+            Ok(image) => image,
+            Err(e) => {
+                // The cached display may be gone; look it up again and retry once
+                debug_line(
+                    1,
+                    &format!("Capture failed ({}), re-enumerating displays...", e),
+                );
+                self.forget_screen();
+                self.screen()?
+                    .capture()
+                    .map_err(|e| anyhow::anyhow!("Failed to capture screen: {}", e))?
+            }
+        };
+
+        // screenshots crate uses image 0.24, we use 0.25
+        // Convert pixel data manually to avoid version conflict
+        let mut rgba_image = RgbaImage::new(captured_image.width(), captured_image.height());
+        for (x, y, pixel) in captured_image.enumerate_pixels() {
+            // Manually copy RGBA values
+            let rgba = Rgba([pixel[0], pixel[1], pixel[2], pixel[3]]);
+            rgba_image.put_pixel(x, y, rgba);
+        }
+
+        Ok(rgba_image)
+    }
+
+    /// Grabs the whole desktop through the xdg-desktop-portal Screenshot
+    /// interface, the only capture path available to Wayland clients
+    #[cfg(target_os = "linux")]
+    fn capture_with_portal() -> Result<RgbaImage> {
+        use ashpd::desktop::screenshot::Screenshot;
+
+        let screenshot = pollster::block_on(async {
+            Screenshot::request()
+                .interactive(false)
+                .modal(false)
+                .send()
+                .await?
+                .response()
+        })
+        .map_err(|e| anyhow::anyhow!("Desktop portal screenshot failed: {}", e))?;
+
+        let path = screenshot.uri().to_file_path().map_err(|_| {
+            anyhow::anyhow!(
+                "Portal returned a non-file screenshot URI: {}",
+                screenshot.uri()
+            )
+        })?;
+        let image = image::open(&path)
+            .map_err(|e| {
+                anyhow::anyhow!("Failed to read portal screenshot {}: {}", path.display(), e)
+            })?
+            .to_rgba8();
+
+        // The portal saves every frame into the user's pictures folder
+        let _ = std::fs::remove_file(&path);
+        Ok(image)
+    }
+
+    fn apply_color_mode(&self, img: RgbaImage) -> RgbaImage {
+        if !self.grayscale {
+            return img;
+        }
+
+        // Keep the RGBA layout so comparison and stitching work unchanged
+        image::DynamicImage::ImageLuma8(image::imageops::grayscale(&img)).to_rgba8()
+    }
+
+    #[cfg(target_os = "macos")]
+    fn cgimage_to_rgba(cg_image: &CGImageRef) -> RgbaImage {
+        let width = cg_image.width() as u32;
+        let height = cg_image.height() as u32;
+        let bytes_per_row = cg_image.bytes_per_row();
+        let data = cg_image.data();
+        let bytes = data.bytes();
+
+        let mut img_buffer = ImageBuffer::new(width, height);
+
+        for y in 0..height {
+            for x in 0..width {
+                let offset = (y as usize * bytes_per_row) + (x as usize * 4);
+                if offset + 3 < bytes.len() {
+                    let pixel = Rgba([
+                        bytes[offset + 2],
+                        bytes[offset + 1],
+                        bytes[offset],
+                        bytes[offset + 3],
+                    ]);
+                    img_buffer.put_pixel(x, y, pixel);
+                }
+            }
+        }
+
+        img_buffer
+    }
+
+    /// `overlap`, or the `with_overlap_percent` share of `frame_length`
+    fn effective_overlap(
+        &self,
+        overlap: u32,
+        frame_length: u32,
+        logs: &Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>,
+    ) -> u32 {
+        let Some(percent) = self.overlap_percent else {
+            return overlap;
+        };
+        let pixels = (frame_length as f32 * percent / 100.0).round() as u32;
+        Self::log_msg(
+            logs,
+            &format!("Overlap {}% of {}px = {}px", percent, frame_length, pixels),
+        );
+        pixels
+    }
+
+    /// Wheel clicks per scroll step: the configured amount, or enough clicks to
+    /// advance one frame minus the overlap so fixed-overlap stitching lines up.
+    fn wheel_clicks(&self, frame_length: u32, overlap: u32) -> i32 {
+        match self.wheel_amount {
+            Some(clicks) => clicks as i32,
+            None => (frame_length.saturating_sub(overlap) / scroll::PIXELS_PER_WHEEL_CLICK).max(1)
+                as i32,
+        }
+    }
+
+    /// Sets up keyboard/mouse simulation once per capture; fails with
+    /// `CaptureError::InputPermissionDenied` when input can't be sent
+    fn input_device() -> std::result::Result<Enigo, CaptureError> {
+        Enigo::new(&Settings::default())
+            .map_err(|e| CaptureError::InputPermissionDenied(e.to_string()))
+    }
+
+    fn scroll_down(
+        &self,
+        enigo: &mut Enigo,
+        key: &ScrollInput,
+        direction: ScrollDirection,
+        wheel_clicks: i32,
+    ) -> std::result::Result<(), CaptureError> {
+        use enigo::{Axis, Mouse};
+
+        // Sequences are replayed exactly as given
+        let scroll_key = match key {
+            ScrollInput::Key(scroll_key) => *scroll_key,
+            ScrollInput::Sequence { chords, .. } => {
+                for (i, chord) in chords.iter().enumerate() {
+                    if i > 0 {
+                        thread::sleep(Duration::from_millis(timing::KEY_SEQUENCE_GAP_MS));
+                    }
+                    Self::press_chord(enigo, chord)?;
+                }
+                thread::sleep(Duration::from_millis(timing::SCROLL_WAIT_MS)); // Wait for content to load
+                return Ok(());
+            }
+        };
+
+        let axis = match direction {
+            ScrollDirection::Vertical => Axis::Vertical,
+            ScrollDirection::Horizontal => Axis::Horizontal,
+        };
+
+        match scroll_key.as_key(direction) {
+            Some(key) => enigo.key(key, enigo::Direction::Click)?,
+            None => enigo.scroll(wheel_clicks, axis)?,
+        }
+        thread::sleep(Duration::from_millis(timing::SCROLL_WAIT_MS)); // Wait for content to load
+        Ok(())
+    }
+
+    /// Holds the chord's modifiers, clicks its key, then releases the
+    /// modifiers in reverse order even if the click failed
+    fn press_chord(enigo: &mut Enigo, chord: &KeyChord) -> std::result::Result<(), CaptureError> {
+        for &modifier in &chord.modifiers {
+            enigo.key(modifier, enigo::Direction::Press)?;
+        }
+        let result = enigo.key(chord.key, enigo::Direction::Click);
+        for &modifier in chord.modifiers.iter().rev() {
+            enigo.key(modifier, enigo::Direction::Release)?;
+        }
+        result?;
+        Ok(())
+    }
+
+    /// Jumps to the start of the page: Cmd+Up on macOS, Home elsewhere.
+    /// There is no horizontal equivalent, so horizontal mode wheels left instead.
+    fn scroll_to_top(
+        &self,
+        enigo: &mut Enigo,
+        direction: ScrollDirection,
+    ) -> std::result::Result<(), CaptureError> {
+        use enigo::{Axis, Mouse};
+
+        match direction {
+            ScrollDirection::Vertical => {
+                #[cfg(target_os = "macos")]
+                {
+                    enigo.key(Key::Meta, enigo::Direction::Press)?;
+                    let result = enigo.key(Key::UpArrow, enigo::Direction::Click);
+                    enigo.key(Key::Meta, enigo::Direction::Release)?;
+                    result?;
+                }
+                #[cfg(not(target_os = "macos"))]
+                enigo.key(Key::Home, enigo::Direction::Click)?;
+            }
+            ScrollDirection::Horizontal => {
+                enigo.scroll(-scroll::HOME_WHEEL_CLICKS, Axis::Horizontal)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Pixel-precise scroll events go through Core Graphics directly, so the
+    /// shared `Enigo` is only taken to keep the signature uniform
+    #[cfg(target_os = "macos")]
+    fn scroll_by_pixels(
+        &self,
+        _enigo: &mut Enigo,
+        pixels: u32,
+    ) -> std::result::Result<(), CaptureError> {
+        use core_graphics::event::{CGEvent, CGEventTapLocation, ScrollEventUnit};
+        use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
+
+        let source = CGEventSource::new(CGEventSourceStateID::HIDSystemState)
+            .map_err(|_| anyhow::anyhow!("Failed to create event source"))?;
+
+        // Negative wheel values scroll the content down
+        let event =
+            CGEvent::new_scroll_event(source, ScrollEventUnit::PIXEL, 1, -(pixels as i32), 0, 0)
+                .map_err(|_| anyhow::anyhow!("Failed to create scroll event"))?;
+        event.post(CGEventTapLocation::HID);
+
+        thread::sleep(Duration::from_millis(timing::SCROLL_WAIT_MS));
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn scroll_by_pixels(
+        &self,
+        enigo: &mut Enigo,
+        pixels: u32,
+    ) -> std::result::Result<(), CaptureError> {
+        use enigo::{Axis, Mouse};
+
+        // No pixel-precise scroll events here, so approximate with wheel clicks
+        let clicks = pixels
+            .div_ceil(constants::scroll::PIXELS_PER_WHEEL_CLICK)
+            .max(1);
+        enigo.scroll(clicks as i32, Axis::Vertical)?;
+
+        thread::sleep(Duration::from_millis(timing::SCROLL_WAIT_MS));
+        Ok(())
+    }
+
+    fn images_are_identical(&self, img1: &RgbaImage, img2: &RgbaImage) -> bool {
+        // Check if images have the same dimensions
+        if img1.width() != img2.width() || img1.height() != img2.height() {
+            debug_line(
+                2,
+                &format!(
+                    "Size mismatch: {}x{} vs {}x{}",
+                    img1.width(),
+                    img1.height(),
+                    img2.width(),
+                    img2.height()
+                ),
+            );
+            return false;
+        }
+
+        let width = img1.width();
+        let height = img1.height();
+        let total_pixels = (width * height) as usize;
+
+        debug_line(
+            2,
+            &format!(
+                "Comparing entire images: {}x{} ({} pixels)",
+                width, height, total_pixels
+            ),
+        );
+
+        if !stitch::images_are_identical(img1, img2) {
+            debug_line(2, "Found different pixels");
+            return false;
+        }
+
+        debug_line(2, "Images are completely identical");
+        true
+    }
+
+    /// Shannon entropy (in bits, 0-8) of the band's luma histogram.
+    /// Near-blank content scores close to 0, busy content close to 8.
+    fn content_entropy(band: &RgbaImage) -> f32 {
+        let mut histogram = [0usize; 256];
+        for pixel in band.pixels() {
+            let luma =
+                (pixel[0] as u32 * 299 + pixel[1] as u32 * 587 + pixel[2] as u32 * 114) / 1000;
+            histogram[luma as usize] += 1;
+        }
+
+        let total = (band.width() * band.height()) as f32;
+        if total == 0.0 {
+            return 0.0;
+        }
+
+        histogram
+            .iter()
+            .filter(|&&count| count > 0)
+            .map(|&count| {
+                let p = count as f32 / total;
+                -p * p.log2()
+            })
+            .sum()
+    }
+
+    /// Compares the bottom `overlap_height` rows of both frames against the
+    /// configured (or adaptive) threshold; see [`stitch::images_are_similar`].
+    fn images_are_similar(
+        &self,
+        img1: &RgbaImage,
+        img2: &RgbaImage,
+        overlap_height: u32,
+        tolerance: u8,
+    ) -> (bool, f32) {
+        if img1.dimensions() != img2.dimensions() {
+            return (false, 100.0);
+        }
+
+        let (width, height) = img1.dimensions();
+        let band_height = stitch::band_height(height, overlap_height);
+        let columns = stitch::centered_columns(width, self.compare_width_fraction);
+
+        let tolerance = match self.metric {
+            SimilarityMetric::ExactDiff | SimilarityMetric::Tolerance(_) => tolerance,
+            SimilarityMetric::Ssim { threshold } => {
+                let score = stitch::band_ssim(img1, img2, overlap_height, columns);
+                debug_line(
+                    1,
+                    &format!(
+                        "Bottom {}px SSIM {:.4} (threshold {:.4})",
+                        band_height, score, threshold
+                    ),
+                );
+                return (score >= threshold, (1.0 - score).max(0.0) * 100.0);
+            }
+        };
+
+        let mut threshold = self.diff_threshold();
+        if self.adaptive_threshold {
+            let band = image::imageops::crop_imm(img2, 0, height - band_height, width, band_height)
+                .to_image();
+            let entropy = Self::content_entropy(&band);
+            let scale = similarity::ADAPTIVE_MIN_SCALE
+                + (similarity::ADAPTIVE_MAX_SCALE - similarity::ADAPTIVE_MIN_SCALE)
+                    * (entropy / similarity::MAX_ENTROPY_BITS);
+            threshold *= scale;
+
+            debug_line(
+                2,
+                &format!(
+                    "Band entropy {:.2} bits, adaptive threshold {:.4}%",
+                    entropy, threshold
+                ),
+            );
+        }
+
+        let (is_similar, diff_percentage) = stitch::images_are_similar_in_columns(
+            img1,
+            img2,
+            overlap_height,
+            tolerance,
+            threshold,
+            columns,
+        );
+        debug_line(
+            1,
+            &format!(
+                "Bottom {}px differs by {:.4}% (threshold {:.4}%)",
+                band_height, diff_percentage, threshold
+            ),
+        );
+        (is_similar, diff_percentage)
+    }
+
+    /// Counts differing pixels inside and outside the excluded bands,
+    /// returned as `(band_diffs, content_diffs)`.
+    fn count_diffs_by_band(&self, img1: &RgbaImage, img2: &RgbaImage) -> (usize, usize) {
+        if img1.dimensions() != img2.dimensions() {
+            // Treat a size change as a content change
+            return (0, usize::MAX);
+        }
+
+        let (width, height) = img1.dimensions();
+        let bands = &self.excluded_bands;
+        let content_bottom = height.saturating_sub(bands.bottom);
+        let content_right = width.saturating_sub(bands.right);
+
+        let mut band_diffs = 0;
+        let mut content_diffs = 0;
+        for y in 0..height {
+            for x in 0..width {
+                if img1.get_pixel(x, y) != img2.get_pixel(x, y) {
+                    if y < bands.top || y >= content_bottom || x >= content_right {
+                        band_diffs += 1;
+                    } else {
+                        content_diffs += 1;
+                    }
+                }
+            }
+        }
+
+        debug_line(
+            2,
+            &format!(
+                "Differences: {} in excluded bands, {} in content",
+                band_diffs, content_diffs
+            ),
+        );
+        (band_diffs, content_diffs)
+    }
+
+    /// Finds how many rows of `img2`'s top repeat `img1`'s bottom by sliding
+    /// one over the other and picking the offset with the lowest pixel difference.
+    /// Only overlaps up to half the frame are tried, as every candidate costs
+    /// a pass over its rows. Returns `None` when no candidate matches closely enough.
+    fn detect_overlap(&self, img1: &RgbaImage, img2: &RgbaImage) -> Option<u32> {
+        self.detect_overlap_in(img1, img2, img1.height().min(img2.height()) / 2)
+    }
+
+    /// `detect_overlap` limited to candidates up to `max_overlap` rows
+    fn detect_overlap_in(
+        &self,
+        img1: &RgbaImage,
+        img2: &RgbaImage,
+        max_overlap: u32,
+    ) -> Option<u32> {
+        let (overlap, mean_diff) = stitch::best_overlap(img1, img2, max_overlap)?;
+        debug_line(
+            1,
+            &format!(
+                "Best overlap {}px (mean channel diff {:.2})",
+                overlap, mean_diff
+            ),
+        );
+
+        if self.overlap_matches(img1, img2, overlap, mean_diff) {
+            Some(overlap)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the `overlap`-row alignment of a frame pair, whose mean
+    /// channel difference is `mean_diff`, is close enough under the metric
+    fn overlap_matches(
+        &self,
+        img1: &RgbaImage,
+        img2: &RgbaImage,
+        overlap: u32,
+        mean_diff: f32,
+    ) -> bool {
+        match self.metric {
+            SimilarityMetric::Ssim { threshold } => {
+                let score = stitch::overlap_ssim(img1, img2, overlap);
+                debug_line(2, &format!("Overlap {}px SSIM {:.4}", overlap, score));
+                score >= threshold
+            }
+            _ => mean_diff <= overlap_detection::MAX_MEAN_DIFF,
+        }
+    }
+
+    /// Auto overlap for a frame pair. Scrolls usually move the same distance
+    /// each time, so the last result is checked first and the half-frame
+    /// sweep only runs when it no longer matches.
+    fn auto_overlap(&self, previous: &RgbaImage, current: &RgbaImage) -> Option<u32> {
+        let mut cache = self.overlap_cache.lock().unwrap();
+
+        if let Some(cached) = *cache
+            && previous.width() == current.width()
+            && cached < previous.height().min(current.height())
+            && self.overlap_matches(
+                previous,
+                current,
+                cached,
+                stitch::overlap_mean_diff(previous, current, cached),
+            )
+        {
+            return Some(cached);
+        }
+
+        let detected = self.detect_overlap(previous, current);
+        if detected.is_some() {
+            *cache = detected;
+        }
+        detected
+    }
+
+    /// Blocks while the pause flag is set, returning early if a stop is requested
+    fn wait_while_paused(
+        &self,
+        stop_flag: &Option<std::sync::Arc<std::sync::Mutex<bool>>>,
+        logs: &Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>,
+    ) {
+        let Some(pause_flag) = &self.pause_flag else {
+            return;
+        };
+        if !*pause_flag.lock().unwrap() {
+            return;
+        }
+
+        Self::log_msg(logs, "Paused");
+        while *pause_flag.lock().unwrap() {
+            if stop_flag.as_ref().is_some_and(|flag| *flag.lock().unwrap()) {
+                return;
+            }
+            thread::sleep(Duration::from_millis(timing::PAUSE_POLL_MS));
+        }
+        Self::log_msg(logs, "Resumed");
+    }
+
+    /// Writes a raw frame to the dump directory, if one is set
+    fn dump_frame(
+        &self,
+        frame: &RgbaImage,
+        number: usize,
+        logs: &Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>,
+    ) -> Result<()> {
+        let Some(dir) = &self.dump_frames else {
+            return Ok(());
+        };
+
+        let path = std::path::Path::new(dir).join(format!("frame_{:04}.png", number));
+        frame
+            .save(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to save frame to {}: {}", path.display(), e))?;
+        Self::log_msg(logs, &format!("Saved frame {}", path.display()));
+        Ok(())
+    }
+
+    /// Overlap to use between two consecutive frames: detected when auto
+    /// overlap is enabled, otherwise (or when detection fails) the fixed value.
+    fn pair_overlap(
+        &self,
+        previous: &RgbaImage,
+        current: &RgbaImage,
+        overlap: u32,
+        logs: &Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>,
+    ) -> u32 {
+        if !self.auto_overlap {
+            return overlap;
+        }
+        self.detected_overlap(previous, current, overlap, logs)
+    }
+
+    /// Detected overlap of a frame pair, or `overlap` when nothing matches
+    fn detected_overlap(
+        &self,
+        previous: &RgbaImage,
+        current: &RgbaImage,
+        overlap: u32,
+        logs: &Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>,
+    ) -> u32 {
+        match self.auto_overlap(previous, current) {
+            Some(detected) => {
+                Self::log_msg(logs, &format!("Detected overlap: {}px", detected));
+                detected
+            }
+            None => {
+                Self::log_msg(
+                    logs,
+                    &format!("No confident overlap match, using {}px", overlap),
+                );
+                overlap
+            }
+        }
+    }
+
+    /// Concatenates frames along the scroll axis; see [`stitch::stitch_images`]
+    fn stitch_images(
+        &self,
+        images: Vec<RgbaImage>,
+        overlaps: &[u32],
+        direction: ScrollDirection,
+    ) -> RgbaImage {
+        let clamped = stitch::clamp_overlaps(&images, overlaps, direction);
+        if clamped
+            .iter()
+            .zip(overlaps)
+            .any(|(used, asked)| used != asked)
+        {
+            print_line(&format!(
+                "Warning: overlap is not smaller than the frame, stitching with {}px instead",
+                clamped.iter().min().copied().unwrap_or(0)
+            ));
+        }
+        stitch::stitch_images(&images, &clamped, direction, self.seam_blend)
+    }
+
+    /// Fails with `EmptyFrame` for a frame without pixels
+    fn ensure_not_empty(
+        frame: &RgbaImage,
+        crop_region: Option<(i32, i32, i32, i32)>,
+    ) -> Result<()> {
+        if frame.width() == 0 || frame.height() == 0 {
+            return Err(CaptureError::EmptyFrame { crop: crop_region }.into());
+        }
+        Ok(())
+    }
+
+    /// Takes a frame from `source`, re-capturing up to `BLANK_FRAME_RETRIES`
+    /// times while the frame is blank so a black frame isn't stitched in
+    fn capture_non_blank(
+        &self,
+        source: &dyn CaptureSource,
+        logs: &Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>,
+    ) -> Result<RgbaImage> {
+        let mut frame = source.capture_frame()?;
+        for attempt in 1..=timing::BLANK_FRAME_RETRIES {
+            // An empty frame won't fill in by waiting; the caller rejects it
+            if frame.is_empty() || !stitch::is_blank(&frame) {
+                break;
+            }
+            Self::log_msg(
+                logs,
+                &format!(
+                    "Captured a blank frame, retrying ({}/{})",
+                    attempt,
+                    timing::BLANK_FRAME_RETRIES
+                ),
+            );
+            thread::sleep(Duration::from_millis(timing::BLANK_FRAME_RETRY_DELAY_MS));
+            frame = source.capture_frame()?;
+        }
+        Ok(frame)
+    }
+
+    /// Polls the screen every `SETTLE_POLL_MS` after a scroll and returns the
+    /// first frame identical to the poll before it, or the latest frame once
+    /// `max_ms` has passed. The first poll waits one interval so the scroll
+    /// has a chance to start before two unchanged frames end the wait.
+    fn settle_until_stable(&self, source: &dyn CaptureSource, max_ms: u64) -> Result<RgbaImage> {
+        let poll = Duration::from_millis(timing::SETTLE_POLL_MS);
+        let deadline = std::time::Instant::now() + Duration::from_millis(max_ms);
+
+        thread::sleep(poll);
+        let mut previous = source.capture_frame()?;
+        while std::time::Instant::now() < deadline {
+            thread::sleep(poll);
+            let current = source.capture_frame()?;
+            if self.images_are_identical(&previous, &current) {
+                return Ok(current);
+            }
+            previous = current;
+        }
+
+        debug_line(
+            1,
+            &format!("Screen still changing after {}ms, capturing anyway", max_ms),
+        );
+        Ok(previous)
+    }
+
+    fn notify_frame(&self, frame: &RgbaImage) {
+        if let Some(observer) = &self.frame_observer {
+            observer(frame);
+        }
+    }
+
+    fn sample_cursor(&self) -> Option<(i32, i32)> {
+        if self.trace_cursor {
+            Self::get_mouse_position().ok()
+        } else {
+            None
+        }
+    }
+
+    /// Translates per-frame screen cursor positions into stitched image coordinates.
+    fn record_cursor_trace(
+        &self,
+        images: &[RgbaImage],
+        overlaps: &[u32],
+        crop_region: Option<(i32, i32, i32, i32)>,
+        samples: &[Option<(i32, i32)>],
+        direction: ScrollDirection,
+    ) {
+        let (origin_x, origin_y) = crop_region.map(|(x, y, _, _)| (x, y)).unwrap_or((0, 0));
+        let offsets = stitch::frame_offsets(images, overlaps, direction);
+
+        let mut trace = Vec::new();
+        for (i, sample) in samples.iter().enumerate() {
+            let Some((screen_x, screen_y)) = *sample else {
+                continue;
+            };
+
+            let frame_x = screen_x - origin_x;
+            let mut frame_y = screen_y - origin_y;
+            if i == 0 && direction == ScrollDirection::Vertical {
+                frame_y -= self.trim_first_top as i32;
+            }
+
+            // Skip samples where the cursor was outside the captured frame
+            let frame = &images[i];
+            if frame_x < 0
+                || frame_y < 0
+                || frame_x >= frame.width() as i32
+                || frame_y >= frame.height() as i32
+            {
+                continue;
+            }
+
+            trace.push(match direction {
+                ScrollDirection::Vertical => (frame_x, frame_y + offsets[i] as i32),
+                ScrollDirection::Horizontal => (frame_x + offsets[i] as i32, frame_y),
+            });
+        }
+
+        *self.cursor_trace.lock().unwrap() = trace;
+    }
+
+    /// Counts leading rows (from the top, or from the bottom when `from_bottom`)
+    /// that stay the same in at least `agreement` of the consecutive frame pairs
+    fn sticky_rows(frames: &[&RgbaImage], agreement: f32, tolerance: u8, from_bottom: bool) -> u32 {
+        let height = frames[0].height();
+        let max_rows = (height as f32 * sticky::MAX_BAND_FRACTION) as u32;
+        let pairs = frames.len() - 1;
+
+        let row_matches = |a: &RgbaImage, b: &RgbaImage, y: u32| {
+            (0..a.width()).all(|x| {
+                let (p1, p2) = (a.get_pixel(x, y), b.get_pixel(x, y));
+                (0..3).all(|c| p1[c].abs_diff(p2[c]) <= tolerance)
+            })
+        };
+
+        let mut rows = 0;
+        while rows < max_rows {
+            let y = if from_bottom { height - 1 - rows } else { rows };
+            let agreeing = frames
+                .windows(2)
+                .filter(|pair| row_matches(pair[0], pair[1], y))
+                .count();
+            if (agreeing as f32) < agreement * pairs as f32 {
+                break;
+            }
+            rows += 1;
+        }
+        rows
+    }
+
+    /// Detects a fixed header and footer repeated in every frame and crops the
+    /// header off all frames but the first and the footer off all but the
+    /// last, so each appears once in the stitched image.
+    fn trim_sticky_bands(
+        &self,
+        images: &mut [RgbaImage],
+        overlaps: &mut [u32],
+        direction: ScrollDirection,
+        tolerance: u8,
+        logs: &Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>,
+    ) {
+        let Some(agreement) = self.sticky_agreement else {
+            return;
+        };
+
+        if direction == ScrollDirection::Horizontal {
+            Self::log_msg(
+                logs,
+                "Ignoring sticky header/footer trim in horizontal mode",
+            );
+            return;
+        }
+
+        // The first frame may be shorter after --trim-first-top, so rows are
+        // compared only across frames sharing the last frame's size
+        let Some(last) = images.last() else {
+            return;
+        };
+        let frames: Vec<&RgbaImage> = images
+            .iter()
+            .filter(|img| img.dimensions() == last.dimensions())
+            .collect();
+        if frames.len() < 2 {
+            return;
+        }
+
+        let header = Self::sticky_rows(&frames, agreement, tolerance, false);
+        let footer = Self::sticky_rows(&frames, agreement, tolerance, true);
+        if header == 0 && footer == 0 {
+            Self::log_msg(logs, "No sticky header or footer detected");
+            return;
+        }
+        Self::log_msg(
+            logs,
+            &format!(
+                "Sticky header {}px, footer {}px; trimming repeats",
+                header, footer
+            ),
+        );
+
+        let count = images.len();
+        for (i, img) in images.iter_mut().enumerate() {
+            let top = if i > 0 { header } else { 0 };
+            let bottom = if i + 1 < count { footer } else { 0 };
+            if top + bottom >= img.height() {
+                continue;
+            }
+            *img =
+                image::imageops::crop_imm(&*img, 0, top, img.width(), img.height() - top - bottom)
+                    .to_image();
+        }
+
+        // The bands were counted in every overlap but now exist only once
+        for overlap in overlaps.iter_mut() {
+            *overlap = overlap.saturating_sub(header + footer);
+        }
+    }
 
-        // Offer to enable zoom automatically
-        print!("Do you want to enable Magnifier now? (y/N): ");
-        io::stdout().flush()?;
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+    fn trim_first_frame(
+        &self,
+        img: &RgbaImage,
+        overlap: u32,
+        direction: ScrollDirection,
+        logs: &Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>,
+    ) -> Result<RgbaImage> {
+        if self.trim_first_top == 0 {
+            return Ok(img.clone());
+        }
 
-        if input.trim().to_lowercase() == "y" {
-            println!("Enabling Magnifier...");
-            Self::enable_zoom()?;
-            thread::sleep(Duration::from_millis(timing::ZOOM_ENABLE_DELAY_MS));
+        if direction == ScrollDirection::Horizontal {
+            // Every frame keeps its full height side by side, so trimming
+            // only the first one would leave a ragged edge
+            Self::log_msg(logs, "Ignoring first-frame trim in horizontal mode");
+            return Ok(img.clone());
         }
 
-        println!();
-        println!("Step 1/2: Position mouse at TOP-LEFT corner");
+        if self.trim_first_top + overlap >= img.height() {
+            return Err(anyhow::anyhow!(
+                "Trim of {}px plus {}px overlap does not fit in the {}px tall first frame",
+                self.trim_first_top,
+                overlap,
+                img.height()
+            ));
+        }
+
+        Self::log_msg(
+            logs,
+            &format!("Trimming {}px from top of first frame", self.trim_first_top),
+        );
+        let trimmed = image::imageops::crop_imm(
+            img,
+            0,
+            self.trim_first_top,
+            img.width(),
+            img.height() - self.trim_first_top,
+        )
+        .to_image();
+
+        Ok(trimmed)
+    }
+
+    fn log_msg(logs: &Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>, msg: &str) {
+        Self::log_event(
+            logs,
+            msg,
+            LogEvent::Message {
+                message: msg.to_string(),
+            },
+        );
+    }
+
+    /// Logs `msg`, or `event` instead on the terminal with `--log-json`
+    fn log_event(
+        logs: &Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>,
+        msg: &str,
+        event: LogEvent,
+    ) {
+        if let Some(logs) = logs {
+            let timestamp = chrono::Local::now().format("%H:%M:%S%.6f");
+            logs.lock()
+                .unwrap()
+                .push(format!("[{}] {}", timestamp, msg));
+        }
+        if LOG_JSON.load(std::sync::atomic::Ordering::Relaxed) {
+            event.emit();
+        } else {
+            print_line(msg);
+        }
+    }
+
+    /// Screen size in the units crops are given in (points on macOS, where
+    /// `to_physical_region` scales them afterwards)
+    fn logical_screen_size(&self) -> Result<(i32, i32)> {
+        let info = self.screen()?.display_info;
+        let scale = info.scale_factor as f64 / self.display_scale_factor();
+        Ok((
+            (info.width as f64 * scale).round() as i32,
+            (info.height as f64 * scale).round() as i32,
+        ))
+    }
+
+    fn to_physical_region(
+        &self,
+        region: (i32, i32, i32, i32),
+        logs: &Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>,
+    ) -> (i32, i32, i32, i32) {
+        let scale = self.display_scale_factor();
+        if scale == 1.0 {
+            return region;
+        }
+
+        let (x, y, w, h) = scale_crop_region(region, scale);
+        Self::log_msg(
+            logs,
+            &format!(
+                "Display scale {:.1}x: capturing {}x{} pixels at ({}, {})",
+                scale, w, h, x, y
+            ),
+        );
+        (x, y, w, h)
+    }
+
+    fn resolve_crop_region(
+        &self,
+        window_only: bool,
+        crop: Option<String>,
+        logs: &Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>,
+    ) -> Result<Option<(i32, i32, i32, i32)>> {
+        // Back to the configured display if a previous capture followed a
+        // window elsewhere
+        if self.window_display.lock().unwrap().take().is_some() {
+            self.forget_screen();
+        }
+
+        // Determine crop region (manual crop takes precedence)
+        let crop_region = if let Some(crop_str) = crop {
+            // Manual crop region
+            let parsed = if presets::uses_screen_size(&crop_str) {
+                let (screen_width, screen_height) = self.logical_screen_size()?;
+                presets::parse_crop_region_with_screen(&crop_str, screen_width, screen_height)
+            } else {
+                Self::parse_crop_region(&crop_str)
+            };
+            if let Some((x, y, w, h)) = parsed {
+                Self::log_msg(logs, &format!("Manual crop: {}x{} at ({}, {})", w, h, x, y));
+                let region = self.to_physical_region((x, y, w, h), logs);
+                // Fail fast rather than silently capturing the full screen
+                self.validate_crop_region(region)?;
+                Some(region)
+            } else {
+                return Err(CaptureError::InvalidCropFormat(crop_str).into());
+            }
+        } else if let Some(title) = &self.window_title {
+            // Raised first, so a window behind another is what gets captured
+            // and receives the scroll keys
+            let Some((x, y, w, h)) = self.activate_window_by_title(title)? else {
+                return Err(anyhow::anyhow!("No window matching '{}'", title));
+            };
+            thread::sleep(Duration::from_millis(timing::SMALL_DELAY_MS));
+            Self::log_msg(
+                logs,
+                &format!("Window '{}': {}x{} at ({}, {})", title, w, h, x, y),
+            );
+            // Window bounds are global; crops are relative to the captured display
+            let info = self.follow_window((x, y, w, h), logs)?;
+            Some(self.to_physical_region((x - info.x, y - info.y, w, h), logs))
+        } else if window_only {
+            // Auto-detect focused window
+            if let Some((x, y, w, h)) = self.get_focused_window_bounds()? {
+                Self::log_msg(
+                    logs,
+                    &format!("Focused window: {}x{} at ({}, {})", w, h, x, y),
+                );
+                // Window bounds are global; crops are relative to the captured display
+                let info = self.follow_window((x, y, w, h), logs)?;
+                Some(self.to_physical_region((x - info.x, y - info.y, w, h), logs))
+            } else {
+                Self::log_msg(
+                    logs,
+                    "Could not detect focused window, capturing full screen",
+                );
+                None
+            }
+        } else {
+            None
+        };
+
+        Ok(crop_region)
+    }
+
+    /// Captures a list of `item_count` rows of `item_height` pixels each.
+    ///
+    /// Instead of detecting the end of content, the number of frames is computed
+    /// from the list length and every scroll advances by a whole number of items,
+    /// so the overlap between frames is known exactly. Similarity checks are
+    /// skipped because lists often contain repeating rows. Frames share at
+    /// least one item, so `item_height` can be at most half the frame height.
+    pub fn capture_item_list(
+        &self,
+        item_height: u32,
+        item_count: usize,
+        delay: u64,
+        window_only: bool,
+        crop: Option<String>,
+        scroll_delay_ms: u64,
+    ) -> std::result::Result<RgbaImage, CaptureError> {
+        Ok(self.capture_item_list_impl(
+            item_height,
+            item_count,
+            delay,
+            window_only,
+            crop,
+            scroll_delay_ms,
+        )?)
+    }
+
+    fn capture_item_list_impl(
+        &self,
+        item_height: u32,
+        item_count: usize,
+        delay: u64,
+        window_only: bool,
+        crop: Option<String>,
+        scroll_delay_ms: u64,
+    ) -> Result<RgbaImage> {
+        let logs = None;
+
+        Self::log_msg(
+            &logs,
+            &format!("Starting item list capture in {} seconds...", delay),
+        );
+        Self::log_msg(&logs, "Please focus on the window you want to capture!");
+        thread::sleep(Duration::from_secs(delay));
+
+        let crop_region = self.resolve_crop_region(window_only, crop, &logs)?;
+
+        let first_capture = self.capture_screen(crop_region)?;
+        let frame_height = first_capture.height();
+
+        if item_height == 0 || item_height > frame_height / 2 {
+            return Err(anyhow::anyhow!(
+                "Item height must be between 1 and half the frame height ({}px)",
+                frame_height / 2
+            ));
+        }
+
+        // Scroll by all but one of the whole items that fit in a frame, so a
+        // slight overshoot still leaves an item of overlap instead of losing rows
+        let items_per_frame = frame_height / item_height;
+        let step = (items_per_frame - 1) * item_height;
+        let overlap = frame_height - step;
+
+        let list_height = item_height as u64 * item_count as u64;
+        let frame_count = if list_height <= frame_height as u64 {
+            1
+        } else {
+            1 + (list_height - frame_height as u64).div_ceil(step as u64) as usize
+        };
+
+        Self::log_msg(
+            &logs,
+            &format!(
+                "Layout: {} items per frame, {}px per scroll, {}px overlap, {} frames",
+                items_per_frame, step, overlap, frame_count
+            ),
+        );
+        if !cfg!(target_os = "macos") {
+            Self::log_msg(
+                &logs,
+                &format!(
+                    "Warning: scrolling here moves by wheel clicks of ~{}px, not by pixels, so frames may drift off the item grid; check the result",
+                    constants::scroll::PIXELS_PER_WHEEL_CLICK
+                ),
+            );
+        }
+        Self::log_event(
+            &logs,
+            &format!(
+                "Captured screen 1/{} ({}x{})",
+                frame_count,
+                first_capture.width(),
+                first_capture.height()
+            ),
+            LogEvent::Captured {
+                frame: 1,
+                w: first_capture.width(),
+                h: first_capture.height(),
+            },
+        );
+
+        let mut images = vec![self.trim_first_frame(
+            &first_capture,
+            overlap,
+            ScrollDirection::Vertical,
+            &logs,
+        )?];
+
+        let mut enigo = Self::input_device()?;
+        for frame in 2..=frame_count {
+            self.scroll_by_pixels(&mut enigo, step)?;
+
+            // Wait for content to settle after scrolling
+            thread::sleep(Duration::from_millis(scroll_delay_ms));
+
+            let current_capture = self.capture_screen(crop_region)?;
+            Self::log_event(
+                &logs,
+                &format!(
+                    "Captured screen {}/{} ({}x{})",
+                    frame,
+                    frame_count,
+                    current_capture.width(),
+                    current_capture.height()
+                ),
+                LogEvent::Captured {
+                    frame,
+                    w: current_capture.width(),
+                    h: current_capture.height(),
+                },
+            );
+            images.push(current_capture);
+        }
+
+        Self::log_event(
+            &logs,
+            &format!("Stitching {} images...", images.len()),
+            LogEvent::Stitching {
+                frames: images.len(),
+            },
+        );
+        let overlaps = vec![overlap; images.len() - 1];
+        let result = self.stitch_images(images, &overlaps, ScrollDirection::Vertical);
+        Self::log_event(
+            &logs,
+            &format!("Done! Final image: {}x{}", result.width(), result.height()),
+            LogEvent::Done {
+                w: result.width(),
+                h: result.height(),
+            },
+        );
+
+        Ok(result)
+    }
+
+    /// Stitches the PNG frames in `dir`, taken in file name order, with the
+    /// same overlap logic as a live capture: each pair's overlap is detected
+    /// when possible, otherwise `overlap` is used. Frames saved by another
+    /// tool or by `with_dump_frames` can be re-stitched this way.
+    pub fn stitch_directory(
+        &self,
+        dir: &std::path::Path,
+        overlap: u32,
+    ) -> std::result::Result<RgbaImage, CaptureError> {
+        Ok(self.stitch_directory_impl(dir, overlap, None)?)
+    }
+
+    /// `stitch_directory` that reports progress to a GUI log
+    pub fn stitch_directory_with_logs(
+        &self,
+        dir: &std::path::Path,
+        overlap: u32,
+        logs: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+    ) -> std::result::Result<RgbaImage, CaptureError> {
+        Ok(self.stitch_directory_impl(dir, overlap, Some(logs))?)
+    }
+
+    fn stitch_directory_impl(
+        &self,
+        dir: &std::path::Path,
+        overlap: u32,
+        logs: Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>,
+    ) -> Result<RgbaImage> {
+        let mut paths: Vec<std::path::PathBuf> = std::fs::read_dir(dir)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", dir.display(), e))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .extension()
+                        .and_then(|e| e.to_str())
+                        .is_some_and(|e| e.eq_ignore_ascii_case("png"))
+            })
+            .collect();
+        paths.sort();
+
+        if paths.is_empty() {
+            return Err(anyhow::anyhow!("No PNG frames found in {}", dir.display()));
+        }
+        Self::log_msg(
+            &logs,
+            &format!("Stitching {} frames from {}", paths.len(), dir.display()),
+        );
+
+        let mut images: Vec<RgbaImage> = Vec::with_capacity(paths.len());
+        for path in &paths {
+            let frame = image::open(path)
+                .map_err(|e| anyhow::anyhow!("Failed to load {}: {}", path.display(), e))?
+                .to_rgba8();
+            if let Some(first) = images.first()
+                && self.direction.breadth(&frame) != self.direction.breadth(first)
+            {
+                return Err(anyhow::anyhow!(
+                    "{} is {}x{}, which doesn't match the first frame ({}x{})",
+                    path.display(),
+                    frame.width(),
+                    frame.height(),
+                    first.width(),
+                    first.height()
+                ));
+            }
+            images.push(self.apply_color_mode(frame));
+        }
+
+        let overlap = self.effective_overlap(overlap, self.direction.length(&images[0]), &logs);
+
+        // Overlap detection compares rows, so horizontal frames keep the fixed value
+        let mut overlaps = Vec::with_capacity(images.len().saturating_sub(1));
+        for pair in images.windows(2) {
+            let detected = match self.direction {
+                ScrollDirection::Vertical => self.detect_overlap(&pair[0], &pair[1]),
+                ScrollDirection::Horizontal => None,
+            };
+            let pair_overlap = detected.unwrap_or(overlap);
+            Self::log_msg(
+                &logs,
+                &format!(
+                    "Frames {}-{}: {}px overlap{}",
+                    overlaps.len() + 1,
+                    overlaps.len() + 2,
+                    pair_overlap,
+                    if detected.is_some() { "" } else { " (fixed)" }
+                ),
+            );
+            overlaps.push(pair_overlap);
+        }
+
+        let direction = self.direction;
+        Ok(self.stitch_images(images, &overlaps, direction))
+    }
+
+    /// Captures the region `count` times, `interval_ms` apart, without
+    /// scrolling, and stacks the frames top to bottom. With `with_animation`
+    /// the frames are also saved as an animation.
+    pub fn capture_timelapse(
+        &self,
+        interval_ms: u64,
+        count: usize,
+        delay: u64,
+        window_only: bool,
+        crop: Option<String>,
+    ) -> std::result::Result<RgbaImage, CaptureError> {
+        Ok(self.capture_timelapse_impl(interval_ms, count, delay, window_only, crop)?)
+    }
+
+    fn capture_timelapse_impl(
+        &self,
+        interval_ms: u64,
+        count: usize,
+        delay: u64,
+        window_only: bool,
+        crop: Option<String>,
+    ) -> Result<RgbaImage> {
+        let logs = None;
+
+        if count == 0 {
+            return Err(anyhow::anyhow!("Timelapse frame count must be at least 1"));
+        }
+
+        Self::log_msg(
+            &logs,
+            &format!(
+                "Starting timelapse in {} seconds: {} frames every {}ms",
+                delay, count, interval_ms
+            ),
+        );
+        thread::sleep(Duration::from_secs(delay));
+
+        let crop_region = self.resolve_crop_region(window_only, crop, &logs)?;
+        let source = ScreenSource::new(self, crop_region);
+
+        if let Some(dir) = &self.dump_frames {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                anyhow::anyhow!("Failed to create frame dump directory {}: {}", dir, e)
+            })?;
+        }
+
+        // Frames are scheduled from the start time so slow captures don't
+        // make the interval drift
+        let start = std::time::Instant::now();
+        let mut frames = Vec::with_capacity(count);
+        for frame in 1..=count {
+            if let Some(flag) = &self.stop_flag
+                && *flag.lock().unwrap()
+                && !frames.is_empty()
+            {
+                Self::log_event(
+                    &logs,
+                    "Stopped by user",
+                    LogEvent::Stopped {
+                        reason: StopReason::User,
+                    },
+                );
+                break;
+            }
+            if !frames.is_empty() && self.time_limit_reached(start, &logs) {
+                break;
+            }
+
+            let due = start + Duration::from_millis(interval_ms * (frame as u64 - 1));
+            if let Some(wait) = due.checked_duration_since(std::time::Instant::now()) {
+                thread::sleep(wait);
+            }
+
+            let current_capture = self.capture_non_blank(&source, &logs)?;
+            Self::log_event(
+                &logs,
+                &format!(
+                    "Captured frame {}/{} ({}x{})",
+                    frame,
+                    count,
+                    current_capture.width(),
+                    current_capture.height()
+                ),
+                LogEvent::Captured {
+                    frame,
+                    w: current_capture.width(),
+                    h: current_capture.height(),
+                },
+            );
+            self.notify_frame(&current_capture);
+            self.dump_frame(&current_capture, frame, &logs)?;
+            frames.push(current_capture);
+        }
+
+        if let Some((path, fps)) = &self.animation {
+            let saved = save_animation(&frames, path, *fps)?;
+            Self::log_msg(
+                &logs,
+                &format!(
+                    "Saved animation ({} frames at {} fps) to {}",
+                    saved, fps, path
+                ),
+            );
+        }
+
+        Self::log_event(
+            &logs,
+            &format!("Stitching {} images...", frames.len()),
+            LogEvent::Stitching {
+                frames: frames.len(),
+            },
+        );
+        let overlaps = vec![0; frames.len() - 1];
+        let result = stitch::stitch_images(&frames, &overlaps, ScrollDirection::Vertical, false);
+        Self::log_event(
+            &logs,
+            &format!("Done! Final image: {}x{}", result.width(), result.height()),
+            LogEvent::Done {
+                w: result.width(),
+                h: result.height(),
+            },
+        );
+
+        Ok(result)
+    }
+
+    /// Records frames at `fps` for `duration` seconds while the scroll key is
+    /// pressed continuously in the background, then stitches the unique frames.
+    /// The options' max scrolls and scroll delay don't apply to a recording.
+    pub fn capture_with_video(
+        &self,
+        options: &CaptureOptions,
+        duration: u64,
+        fps: u32,
+    ) -> std::result::Result<RgbaImage, CaptureError> {
+        Ok(self.capture_with_video_impl(options, duration, fps)?)
+    }
+
+    fn capture_with_video_impl(
+        &self,
+        options: &CaptureOptions,
+        duration: u64,
+        fps: u32,
+    ) -> Result<RgbaImage> {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::time::Instant;
+
+        let delay = options.delay;
+        let key = &options.key;
+        let logs = None;
 
-        let (x1, y1) = Self::show_live_coordinates()?;
-        println!("Top-left corner: ({}, {})", x1, y1);
-        println!();
+        if fps == 0 {
+            return Err(anyhow::anyhow!("FPS must be at least 1"));
+        }
 
-        println!("Step 2/2: Position mouse at BOTTOM-RIGHT corner");
+        Self::log_msg(
+            &logs,
+            &format!("Starting video capture in {} seconds...", delay),
+        );
+        Self::log_msg(&logs, "Please focus on the window you want to capture!");
+        Self::log_msg(
+            &logs,
+            &format!(
+                "Recording {} seconds at {} fps while pressing {}",
+                duration,
+                fps,
+                key.to_string().to_uppercase()
+            ),
+        );
+        thread::sleep(Duration::from_secs(delay));
 
-        let (x2, y2) = Self::show_live_coordinates()?;
-        println!("Bottom-right corner: ({}, {})", x2, y2);
-        println!();
+        let crop_region =
+            self.resolve_crop_region(options.window_only, options.crop.clone(), &logs)?;
 
-        // Calculate region
-        let x = x1.min(x2);
-        let y = y1.min(y2);
-        let width = (x2 - x1).abs();
-        let height = (y2 - y1).abs();
+        // Grab the starting frame before any scrolling so the top is never missed
+        let first_frame = self.capture_screen(crop_region)?;
+        let overlap =
+            self.effective_overlap(options.overlap, self.direction.length(&first_frame), &logs);
+        let wheel_clicks = self.wheel_clicks(self.direction.length(&first_frame), overlap);
 
-        if width <= 0 || height <= 0 {
-            return Err(anyhow::anyhow!(
-                "Invalid region: width and height must be positive"
-            ));
-        }
+        let frame_interval = Duration::from_secs_f64(1.0 / fps as f64);
+        let recording_done = AtomicBool::new(false);
 
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-        println!("Region selected:");
-        println!("   Position: ({}, {})", x, y);
-        println!("   Size: {}x{}", width, height);
-        println!();
-        println!("Use this command:");
-        println!("   --crop \"{},{},{},{}\"", x, y, width, height);
-        println!();
+        let (frames, scroll_result) = thread::scope(|scope| {
+            let scroller = scope.spawn(|| -> Result<()> {
+                let mut enigo = Self::input_device()?;
+                while !recording_done.load(Ordering::Relaxed) {
+                    self.scroll_down(&mut enigo, key, self.direction, wheel_clicks)?;
+                }
+                Ok(())
+            });
 
-        Ok((x, y, width, height))
-    }
+            let mut frames = vec![first_frame];
+            let start = Instant::now();
+            let mut recorded = 1;
+            let mut capture_error = None;
 
-    #[cfg(target_os = "macos")]
-    fn get_focused_window_bounds(&self) -> Result<Option<(i32, i32, i32, i32)>> {
-        let script = r#"
-tell application "System Events"
-    set frontApp to first application process whose frontmost is true
-    set frontWindow to front window of frontApp
-    set windowPosition to position of frontWindow
-    set windowSize to size of frontWindow
-    return (item 1 of windowPosition) & "," & (item 2 of windowPosition) & "," & (item 1 of windowSize) & "," & (item 2 of windowSize)
-end tell
-"#;
+            while start.elapsed() < Duration::from_secs(duration) {
+                let frame_start = Instant::now();
 
-        let output = std::process::Command::new("osascript")
-            .arg("-e")
-            .arg(script)
-            .output();
+                match self.capture_screen(crop_region) {
+                    Ok(frame) => {
+                        recorded += 1;
+                        // Drop frames where nothing moved since the last kept one
+                        let is_duplicate = frames
+                            .last()
+                            .is_some_and(|last| self.images_are_identical(last, &frame));
+                        if !is_duplicate {
+                            frames.push(frame);
+                        }
+                    }
+                    Err(e) => {
+                        capture_error = Some(e);
+                        break;
+                    }
+                }
 
-        if let Ok(output) = output {
-            if output.status.success() {
-                let result = String::from_utf8_lossy(&output.stdout);
-                let parts: Vec<i32> = result
-                    .trim()
-                    .split(',')
-                    .filter_map(|s| s.trim().parse().ok())
-                    .collect();
-
-                if parts.len() == 4 {
-                    return Ok(Some((parts[0], parts[1], parts[2], parts[3])));
+                if let Some(remaining) = frame_interval.checked_sub(frame_start.elapsed()) {
+                    thread::sleep(remaining);
                 }
             }
-        }
 
-        Ok(None)
-    }
+            recording_done.store(true, Ordering::Relaxed);
+            let scroll_result = scroller
+                .join()
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("Scroll thread panicked")));
 
-    #[cfg(target_os = "windows")]
-    fn get_focused_window_bounds(&self) -> Result<Option<(i32, i32, i32, i32)>> {
-        unsafe {
-            let hwnd = GetForegroundWindow();
-            if hwnd.0 == std::ptr::null_mut() {
-                return Ok(None);
-            }
+            Self::log_msg(
+                &logs,
+                &format!("Recorded {} frames, {} unique", recorded, frames.len()),
+            );
 
-            let mut rect = RECT::default();
-            if GetWindowRect(hwnd, &mut rect).is_ok() {
-                let x = rect.left;
-                let y = rect.top;
-                let width = rect.right - rect.left;
-                let height = rect.bottom - rect.top;
-                return Ok(Some((x, y, width, height)));
+            match capture_error {
+                Some(e) => (Err(e), scroll_result),
+                None => (Ok(frames), scroll_result),
             }
+        });
+
+        scroll_result?;
+        let frames = frames?;
+
+        self.stitch_recorded_frames(frames, overlap, &logs)
+    }
+
+    /// Stitches the unique frames of a recording, as `capture_with_video`
+    /// does once recording ends. `overlap` is the fallback for pairs whose
+    /// overlap can't be detected.
+    fn stitch_recorded_frames(
+        &self,
+        frames: Vec<RgbaImage>,
+        overlap: u32,
+        logs: &Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>,
+    ) -> Result<RgbaImage> {
+        if frames.is_empty() {
+            return Err(anyhow::anyhow!("No frames were recorded"));
         }
-        Ok(None)
+
+        let mut images = vec![self.trim_first_frame(&frames[0], overlap, self.direction, logs)?];
+        let mut overlaps = Vec::new();
+        // Frames sampled from a continuous scroll move by varying amounts, so
+        // each pair's overlap is always detected
+        for frame in frames.into_iter().skip(1) {
+            overlaps.push(self.detected_overlap(
+                &self.direction.scan_view(images.last().unwrap()),
+                &self.direction.scan_view(&frame),
+                overlap,
+                logs,
+            ));
+            images.push(frame);
+        }
+
+        Self::log_event(
+            logs,
+            &format!("Stitching {} images...", images.len()),
+            LogEvent::Stitching {
+                frames: images.len(),
+            },
+        );
+        let result = self.stitch_images(images, &overlaps, self.direction);
+        Self::log_event(
+            logs,
+            &format!("Done! Final image: {}x{}", result.width(), result.height()),
+            LogEvent::Done {
+                w: result.width(),
+                h: result.height(),
+            },
+        );
+
+        Ok(result)
     }
 
-    fn capture_screen(&self, crop_region: Option<(i32, i32, i32, i32)>) -> Result<RgbaImage> {
-        // Try screenshots crate first (more compatible)
-        let screen = screenshots::Screen::all()
-            .map_err(|e| anyhow::anyhow!("Failed to get screens: {}", e))?
-            .into_iter()
-            .next()
-            .ok_or_else(|| anyhow::anyhow!("No screen found"))?;
+    /// Like `capture_with_video`, but decodes the frames from a recorded
+    /// video file at `fps` (with the `ffmpeg` and `ffprobe` tools) instead
+    /// of recording the screen. `crop` takes any `--crop` format, in pixels
+    /// of the video.
+    pub fn stitch_video(
+        &self,
+        path: &std::path::Path,
+        overlap: u32,
+        fps: u32,
+        crop: Option<String>,
+    ) -> std::result::Result<RgbaImage, CaptureError> {
+        Ok(self.stitch_video_impl(path, overlap, fps, crop)?)
+    }
 
-        let captured_image = screen
-            .capture()
-            .map_err(|e| anyhow::anyhow!("Failed to capture screen: {}", e))?;
+    fn stitch_video_impl(
+        &self,
+        path: &std::path::Path,
+        overlap: u32,
+        fps: u32,
+        crop: Option<String>,
+    ) -> Result<RgbaImage> {
+        use std::io::Read;
+        use std::process::{Command, Stdio};
 
-        // screenshots crate uses image 0.24, we use 0.25
-        // Convert pixel data manually to avoid version conflict
-        let width = captured_image.width();
-        let height = captured_image.height();
+        let logs = None;
 
-        let mut rgba_image = RgbaImage::new(width, height);
-        for (x, y, pixel) in captured_image.enumerate_pixels() {
-            // Manually copy RGBA values
-            let rgba = Rgba([pixel[0], pixel[1], pixel[2], pixel[3]]);
-            rgba_image.put_pixel(x, y, rgba);
+        if fps == 0 {
+            return Err(anyhow::anyhow!("FPS must be at least 1"));
+        }
+        if !path.is_file() {
+            return Err(anyhow::anyhow!("Video file not found: {}", path.display()));
         }
 
-        // Apply crop if specified
-        if let Some((crop_x, crop_y, crop_w, crop_h)) = crop_region {
-            // Ensure crop region is within bounds
-            let crop_x = crop_x.max(0) as u32;
-            let crop_y = crop_y.max(0) as u32;
-            let crop_w = crop_w.max(0) as u32;
-            let crop_h = crop_h.max(0) as u32;
-
-            if crop_x + crop_w <= width && crop_y + crop_h <= height {
-                let mut cropped = RgbaImage::new(crop_w, crop_h);
-                for y in 0..crop_h {
-                    for x in 0..crop_w {
-                        let pixel = rgba_image.get_pixel(crop_x + x, crop_y + y);
-                        cropped.put_pixel(x, y, *pixel);
+        let probe = Command::new("ffprobe")
+            .args(["-v", "error", "-select_streams", "v:0"])
+            .args(["-show_entries", "stream=width,height", "-of", "csv=p=0"])
+            .arg(path)
+            .output()
+            .map_err(|e| anyhow::anyhow!("Failed to run ffprobe (is ffmpeg installed?): {}", e))?;
+        let dimensions = String::from_utf8_lossy(&probe.stdout);
+        let Some((width, height)) = dimensions.trim().split_once(',').and_then(|(w, h)| {
+            Some((w.trim().parse::<u32>().ok()?, h.trim().parse::<u32>().ok()?))
+        }) else {
+            return Err(anyhow::anyhow!(
+                "No video stream found in {}: {}",
+                path.display(),
+                String::from_utf8_lossy(&probe.stderr).trim()
+            ));
+        };
+
+        let crop_region = match crop {
+            Some(crop) => {
+                let spec = presets::CropSpec::parse(&crop)
+                    .ok_or_else(|| CaptureError::InvalidCropFormat(crop.clone()))?;
+                let (x, y, w, h) = spec.resolve(width as i32, height as i32);
+                if w <= 0
+                    || h <= 0
+                    || x < 0
+                    || y < 0
+                    || (x + w) as u32 > width
+                    || (y + h) as u32 > height
+                {
+                    return Err(CaptureError::CropOutOfBounds {
+                        crop: (x, y, w, h),
+                        screen: (width, height),
                     }
+                    .into());
                 }
-                return Ok(cropped);
-            } else {
-                println!("Crop region out of bounds, using full screen");
+                Some((x as u32, y as u32, w as u32, h as u32))
             }
-        }
-
-        Ok(rgba_image)
-    }
+            None => None,
+        };
 
-    #[cfg(target_os = "macos")]
-    fn cgimage_to_rgba(cg_image: &CGImageRef) -> RgbaImage {
-        let width = cg_image.width() as u32;
-        let height = cg_image.height() as u32;
-        let bytes_per_row = cg_image.bytes_per_row();
-        let data = cg_image.data();
-        let bytes = data.bytes();
+        Self::log_msg(
+            &logs,
+            &format!(
+                "Decoding {} ({}x{}) at {} fps",
+                path.display(),
+                width,
+                height,
+                fps
+            ),
+        );
 
-        let mut img_buffer = ImageBuffer::new(width, height);
+        // Raw RGBA frames on stdout; -noautorotate keeps them at the size
+        // ffprobe reported
+        let mut child = Command::new("ffmpeg")
+            .args(["-v", "error", "-noautorotate", "-i"])
+            .arg(path)
+            .args(["-vf", &format!("fps={}", fps)])
+            .args(["-f", "rawvideo", "-pix_fmt", "rgba", "-"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("Failed to run ffmpeg: {}", e))?;
+        let mut stdout = child.stdout.take().unwrap();
+        // Drained on its own thread, as a damaged file can fill the pipe with
+        // decode errors while ffmpeg waits for its frames to be read
+        let mut stderr = child.stderr.take().unwrap();
+        let stderr_reader = thread::spawn(move || {
+            let mut text = String::new();
+            let _ = stderr.read_to_string(&mut text);
+            text
+        });
 
-        for y in 0..height {
-            for x in 0..width {
-                let offset = (y as usize * bytes_per_row) + (x as usize * 4);
-                if offset + 3 < bytes.len() {
-                    let pixel = Rgba([
-                        bytes[offset + 2],
-                        bytes[offset + 1],
-                        bytes[offset],
-                        bytes[offset + 3],
-                    ]);
-                    img_buffer.put_pixel(x, y, pixel);
-                }
+        let mut frames: Vec<RgbaImage> = Vec::new();
+        let mut decoded = 0;
+        let mut buffer = vec![0u8; width as usize * height as usize * 4];
+        while stdout.read_exact(&mut buffer).is_ok() {
+            decoded += 1;
+            let full = RgbaImage::from_raw(width, height, buffer.clone()).unwrap();
+            let frame = match crop_region {
+                Some((x, y, w, h)) => image::imageops::crop_imm(&full, x, y, w, h).to_image(),
+                None => full,
+            };
+            // Drop frames where nothing moved since the last kept one
+            let is_duplicate = frames
+                .last()
+                .is_some_and(|last| self.images_are_identical(last, &frame));
+            if !is_duplicate {
+                frames.push(self.apply_color_mode(frame));
             }
         }
 
-        img_buffer
-    }
-
-    fn scroll_down(&self, key_type: &str) -> Result<()> {
-        let mut enigo = Enigo::new(&Settings::default())?;
+        let status = child.wait()?;
+        let stderr = stderr_reader.join().unwrap_or_default();
+        if !status.success() {
+            return Err(anyhow::anyhow!(
+                "ffmpeg failed to decode {}: {}",
+                path.display(),
+                stderr.trim()
+            ));
+        }
 
-        // Select key based on user input
-        let key = match key_type.to_lowercase().as_str() {
-            "down" => Key::DownArrow,
-            "pagedown" => Key::PageDown,
-            _ => Key::Space, // default to Space
+        Self::log_msg(
+            &logs,
+            &format!("Decoded {} frames, {} unique", decoded, frames.len()),
+        );
+        let overlap = match frames.first() {
+            Some(first) => self.effective_overlap(overlap, self.direction.length(first), &logs),
+            None => overlap,
         };
+        self.stitch_recorded_frames(frames, overlap, &logs)
+    }
 
-        enigo.key(key, enigo::Direction::Click)?;
-        thread::sleep(Duration::from_millis(timing::SCROLL_WAIT_MS)); // Wait for content to load
-        Ok(())
+    /// Runs a scroll capture without terminal interaction (no key polling
+    /// or progress bar), for use as a library
+    pub fn run(&self, options: CaptureOptions) -> std::result::Result<RgbaImage, CaptureError> {
+        Ok(self.capture_with_scroll_live(&options, true, None, None)?)
     }
 
-    fn images_are_identical(&self, img1: &RgbaImage, img2: &RgbaImage) -> bool {
-        // Check if images have the same dimensions
-        if img1.width() != img2.width() || img1.height() != img2.height() {
-            println!(
-                "    [DEBUG] Size mismatch: {}x{} vs {}x{}",
-                img1.width(),
-                img1.height(),
-                img2.width(),
-                img2.height()
-            );
-            return false;
-        }
+    /// Checks up front that the mouse position can be read, a frame
+    /// captured and, with `send_input`, input sent, so a missing permission
+    /// fails with guidance before the countdown instead of partway through
+    /// a capture
+    pub fn check_permissions(&self, send_input: bool) -> PermissionStatus {
+        use enigo::{Coordinate, Mouse};
 
-        let width = img1.width();
-        let height = img1.height();
-        let total_pixels = (width * height) as usize;
+        let mut status = PermissionStatus::default();
 
-        println!(
-            "    [DEBUG] Comparing entire images: {}x{} ({} pixels)",
-            width, height, total_pixels
-        );
+        let mouse_position = Self::get_mouse_position().is_ok();
+        status.mouse_position = status.require(mouse_position, input_permission_hint());
 
-        // Compare every pixel
-        let mut diff_count = 0;
-        for y in 0..height {
-            for x in 0..width {
-                if img1.get_pixel(x, y) != img2.get_pixel(x, y) {
-                    diff_count += 1;
-                    // Early exit if we find any difference
-                    if diff_count > 0 {
-                        let diff_percentage = (diff_count as f32 / total_pixels as f32) * 100.0;
-                        println!(
-                            "    [DEBUG] Found {} different pixels ({:.6}%)",
-                            diff_count, diff_percentage
-                        );
-                        return false;
-                    }
-                }
-            }
+        let screen_capture = Self::screen_recording_allowed()
+            && self
+                .capture_backend()
+                .is_ok_and(|frame| frame.width() > 0 && frame.height() > 0);
+        status.screen_capture = status.require(screen_capture, Self::no_screen_hint());
+
+        // A zero-length relative move is an input event nobody notices
+        if send_input {
+            let input_events = Self::input_device()
+                .is_ok_and(|mut enigo| enigo.move_mouse(0, 0, Coordinate::Rel).is_ok());
+            status.input_events = Some(status.require(input_events, input_permission_hint()));
         }
 
-        println!("    [DEBUG] Images are completely identical");
-        true
+        status
     }
 
-    fn stitch_images(&self, images: Vec<RgbaImage>, overlap: u32) -> RgbaImage {
-        if images.is_empty() {
-            return ImageBuffer::new(1, 1);
-        }
-
-        let width = images[0].width();
-        let single_height = images[0].height();
-        let total_height = single_height + (images.len() as u32 - 1) * (single_height - overlap);
+    /// Without Screen Recording permission macOS still returns frames, just
+    /// with only the wallpaper in them, so the capture alone proves nothing
+    #[cfg(target_os = "macos")]
+    fn screen_recording_allowed() -> bool {
+        core_graphics::access::ScreenCaptureAccess.preflight()
+    }
 
-        let mut result = ImageBuffer::new(width, total_height);
+    #[cfg(not(target_os = "macos"))]
+    fn screen_recording_allowed() -> bool {
+        true
+    }
 
-        for (i, img) in images.iter().enumerate() {
-            let y_offset = i as u32 * (single_height - overlap);
+    /// Grabs a single frame of `crop` (any `--crop` format, or the whole
+    /// screen when `None`) without scrolling, e.g. to check a preset
+    pub fn capture_still(
+        &self,
+        crop: Option<String>,
+    ) -> std::result::Result<RgbaImage, CaptureError> {
+        let crop_region = self.resolve_crop_region(false, crop, &None)?;
+        Ok(self.capture_screen(crop_region)?)
+    }
 
-            for y in 0..single_height {
-                for x in 0..width {
-                    let target_y = y_offset + y;
-                    if target_y < total_height {
-                        if i > 0 && y < overlap {
-                            // Use middle of overlap as boundary
-                            if y >= overlap / 2 {
-                                // Bottom half of overlap: use current image
-                                let pixel = img.get_pixel(x, y);
-                                result.put_pixel(x, target_y, *pixel);
-                            }
-                            // Top half: skip (previous image already there)
-                        } else {
-                            // Copy pixel normally (outside overlap region)
-                            let pixel = img.get_pixel(x, y);
-                            result.put_pixel(x, target_y, *pixel);
-                        }
-                    }
-                }
-            }
-        }
+    /// Runs the scroll loop over frames from `source` instead of the screen.
+    /// Sources that aren't live get no scroll input, waits or terminal
+    /// interaction, so a [`MockSource`] makes the loop deterministic. The
+    /// options' delay, key, window and crop settings only apply to the screen.
+    pub fn capture_from_source(
+        &self,
+        source: &dyn CaptureSource,
+        options: &CaptureOptions,
+    ) -> std::result::Result<RgbaImage, CaptureError> {
+        Ok(self.capture_with_scroll_impl(source, options, None, true, None, None)?)
+    }
 
-        result
+    /// Yields each frame as it is captured instead of returning the stitched
+    /// result, so a caller can show progress, stop early and stitch with
+    /// [`stitch`] itself. Nothing happens until the first `next()`, which
+    /// waits out the options' delay.
+    ///
+    /// ```no_run
+    /// use capture::{CaptureOptions, ScreenCapture};
+    ///
+    /// let capture = ScreenCapture::new();
+    /// let mut frames = Vec::new();
+    /// for frame in capture.frames(CaptureOptions::new().with_max_scrolls(Some(5))) {
+    ///     frames.push(frame?);
+    /// }
+    /// # Ok::<(), capture::CaptureError>(())
+    /// ```
+    pub fn frames(&self, options: CaptureOptions) -> Frames<'_> {
+        Frames::new(self, options, None)
     }
 
-    fn log_msg(logs: &Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>, msg: &str) {
-        if let Some(logs) = logs {
-            let timestamp = chrono::Local::now().format("%H:%M:%S%.6f");
-            logs.lock()
-                .unwrap()
-                .push(format!("[{}] {}", timestamp, msg));
-        }
-        println!("{}", msg);
+    /// [`frames`](Self::frames) over frames from `source` instead of the screen
+    pub fn frames_from_source<'a>(
+        &'a self,
+        source: impl CaptureSource + 'a,
+        options: CaptureOptions,
+    ) -> Frames<'a> {
+        Frames::new(self, options, Some(Box::new(source)))
     }
 
+    /// Scroll capture with the terminal progress bar and Q to stop
     pub fn capture_with_scroll(
         &self,
-        overlap: u32,
-        max_scrolls: Option<usize>,
-        delay: u64,
-        key_type: &str,
-        window_only: bool,
-        crop: Option<String>,
-        scroll_delay_ms: u64,
-    ) -> Result<RgbaImage> {
-        self.capture_with_scroll_impl(
-            overlap,
-            max_scrolls,
-            delay,
-            key_type,
-            window_only,
-            crop,
-            scroll_delay_ms,
-            false,
-            None,
-            None,
-        )
+        options: &CaptureOptions,
+    ) -> std::result::Result<RgbaImage, CaptureError> {
+        Ok(self.capture_with_scroll_live(options, false, None, None)?)
     }
 
+    /// Scroll capture without terminal interaction, same as [`Self::run`]
     pub fn capture_with_scroll_no_input(
         &self,
-        overlap: u32,
-        max_scrolls: Option<usize>,
-        delay: u64,
-        key_type: &str,
-        window_only: bool,
-        crop: Option<String>,
-        scroll_delay_ms: u64,
-    ) -> Result<RgbaImage> {
-        self.capture_with_scroll_impl(
-            overlap,
-            max_scrolls,
-            delay,
-            key_type,
-            window_only,
-            crop,
-            scroll_delay_ms,
-            true,
-            None,
-            None,
-        )
+        options: &CaptureOptions,
+    ) -> std::result::Result<RgbaImage, CaptureError> {
+        Ok(self.capture_with_scroll_live(options, true, None, None)?)
     }
 
+    /// Scroll capture that stops once `stop_flag` is set, appending its
+    /// progress messages to `logs`
     pub fn capture_with_scroll_with_stop(
         &self,
-        overlap: u32,
-        max_scrolls: Option<usize>,
-        delay: u64,
-        key_type: &str,
-        window_only: bool,
-        crop: Option<String>,
-        scroll_delay_ms: u64,
+        options: &CaptureOptions,
         stop_flag: std::sync::Arc<std::sync::Mutex<bool>>,
         logs: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
-    ) -> Result<RgbaImage> {
-        self.capture_with_scroll_impl(
-            overlap,
-            max_scrolls,
-            delay,
-            key_type,
-            window_only,
-            crop,
-            scroll_delay_ms,
-            true,
-            Some(stop_flag),
-            Some(logs),
-        )
+    ) -> std::result::Result<RgbaImage, CaptureError> {
+        Ok(self.capture_with_scroll_live(options, true, Some(stop_flag), Some(logs))?)
     }
 
-    fn capture_with_scroll_impl(
+    /// Screen capture entry point: announces the settings, waits `delay`
+    /// seconds for the user to focus the target, resolves the crop region
+    /// and runs the scroll loop on the screen
+    fn capture_with_scroll_live(
         &self,
-        overlap: u32,
-        max_scrolls: Option<usize>,
-        delay: u64,
-        key_type: &str,
-        window_only: bool,
-        crop: Option<String>,
-        scroll_delay_ms: u64,
+        options: &CaptureOptions,
         skip_input: bool,
         stop_flag: Option<std::sync::Arc<std::sync::Mutex<bool>>>,
         logs: Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>,
     ) -> Result<RgbaImage> {
+        let max_scrolls = options.max_scrolls;
+        let delay = options.delay;
+        let key = &options.key;
+        let scroll_delay_ms = options.scroll_delay_ms;
+
         Self::log_msg(
             &logs,
             &format!("Starting scroll capture in {} seconds...", delay),
@@ -581,137 +4891,537 @@ end tell
         Self::log_msg(
             &logs,
             &format!(
-                "The program will press {} key once per capture",
-                key_type.to_uppercase()
+                "The program will press {} key once per capture",
+                key.to_string().to_uppercase()
+            ),
+        );
+        match self.adaptive_delay {
+            Some(max_settle_ms) => Self::log_msg(
+                &logs,
+                &format!("Scroll delay: adaptive (up to {}ms)", max_settle_ms),
+            ),
+            None => Self::log_msg(&logs, &format!("Scroll delay: {}ms", scroll_delay_ms)),
+        }
+        if self.direction == ScrollDirection::Horizontal {
+            Self::log_msg(&logs, "Direction: horizontal (stitching left to right)");
+        }
+        if let Some(max) = max_scrolls {
+            Self::log_msg(&logs, &format!("Max scrolls: {}", max));
+        } else {
+            Self::log_msg(&logs, "Max scrolls: unlimited (press Q to stop)");
+        }
+        thread::sleep(Duration::from_secs(delay));
+
+        let crop_region =
+            self.resolve_crop_region(options.window_only, options.crop.clone(), &logs)?;
+        let source = ScreenSource::new(self, crop_region);
+        self.capture_with_scroll_impl(&source, options, crop_region, skip_input, stop_flag, logs)
+    }
+
+    /// Sets up a scroll capture and takes its first frame: creates the dump
+    /// directory and the input device, scrolls to the top if asked and
+    /// resolves the overlap against the first frame. Shared by the scroll
+    /// loop and [`Frames`].
+    fn start_scroll(
+        &self,
+        source: &dyn CaptureSource,
+        options: &CaptureOptions,
+        crop_region: Option<(i32, i32, i32, i32)>,
+        stop_flag: Option<std::sync::Arc<std::sync::Mutex<bool>>>,
+        logs: &Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>,
+    ) -> Result<(ScrollState, RgbaImage)> {
+        let direction = self.direction;
+        let started = std::time::Instant::now();
+
+        if let Some(dir) = &self.dump_frames {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                anyhow::anyhow!("Failed to create frame dump directory {}: {}", dir, e)
+            })?;
+        }
+
+        // Created once up front so missing input permission fails before
+        // anything is captured; scripted sources need no input at all
+        let mut enigo = source.is_live().then(Self::input_device).transpose()?;
+
+        if let Some(enigo) = enigo.as_mut()
+            && self.home_first
+        {
+            Self::log_msg(logs, "Scrolling to the top before capturing");
+            self.scroll_to_top(enigo, direction)?;
+            // Wait for content to settle after the jump
+            thread::sleep(Duration::from_millis(options.scroll_delay_ms));
+        }
+
+        // A zero-sized crop would otherwise stitch into a 1x1 placeholder
+        if crop_region.is_some_and(|(_, _, width, height)| width <= 0 || height <= 0) {
+            return Err(CaptureError::EmptyFrame { crop: crop_region }.into());
+        }
+
+        let first_capture = self.capture_non_blank(source, logs)?;
+        Self::ensure_not_empty(&first_capture, crop_region)?;
+        Self::log_event(
+            logs,
+            &format!(
+                "Captured screen 1 ({}x{})",
+                first_capture.width(),
+                first_capture.height()
             ),
+            LogEvent::Captured {
+                frame: 1,
+                w: first_capture.width(),
+                h: first_capture.height(),
+            },
         );
-        Self::log_msg(&logs, &format!("Scroll delay: {}ms", scroll_delay_ms));
-        if let Some(max) = max_scrolls {
-            Self::log_msg(&logs, &format!("Max scrolls: {}", max));
-        } else {
-            Self::log_msg(&logs, "Max scrolls: unlimited (press Q to stop)");
+        let overlap =
+            self.effective_overlap(options.overlap, direction.length(&first_capture), logs);
+        self.notify_frame(&first_capture);
+        self.dump_frame(&first_capture, 1, logs)?;
+
+        let wheel_clicks = self.wheel_clicks(direction.length(&first_capture), overlap);
+        if options.key == ScrollInput::Key(ScrollKey::Wheel) {
+            Self::log_msg(
+                logs,
+                &format!(
+                    "Mouse wheel: {} clicks (~{}px) per scroll",
+                    wheel_clicks,
+                    wheel_clicks as u32 * scroll::PIXELS_PER_WHEEL_CLICK
+                ),
+            );
         }
-        thread::sleep(Duration::from_secs(delay));
 
-        // Determine crop region (manual crop takes precedence)
-        let crop_region: Option<(i32, i32, i32, i32)> = if let Some(crop_str) = crop {
-            // Manual crop region
-            if let Some((x, y, w, h)) = Self::parse_crop_region(&crop_str) {
-                Self::log_msg(
-                    &logs,
-                    &format!("Manual crop: {}x{} at ({}, {})", w, h, x, y),
-                );
-                Some((x, y, w, h))
-            } else {
-                Self::log_msg(&logs, "Invalid crop format, capturing full screen");
-                Self::log_msg(
-                    &logs,
-                    "   Use format: 'x,y,width,height' (e.g., '100,50,1920,1080')",
-                );
-                None
-            }
-        } else if window_only {
-            // Auto-detect focused window
-            if let Some((x, y, w, h)) = self.get_focused_window_bounds()? {
-                Self::log_msg(
-                    &logs,
-                    &format!("Focused window: {}x{} at ({}, {})", w, h, x, y),
-                );
-                Some((x, y, w, h))
+        let state = ScrollState {
+            enigo,
+            key: options.key.clone(),
+            overlap,
+            scroll_delay_ms: options.scroll_delay_ms,
+            // Exact frame counts fix the number of scrolls at both ends
+            max_scrolls: match self.exact_frames {
+                Some(frames) => Some(frames.saturating_sub(1)),
+                None => options.max_scrolls,
+            },
+            wheel_clicks,
+            crop_region,
+            stop_flag,
+            started,
+            scroll_count: 0,
+            stall_count: 0,
+            animation_frames: if self.animation.is_some() {
+                vec![first_capture.clone()]
             } else {
-                Self::log_msg(
-                    &logs,
-                    "Could not detect focused window, capturing full screen",
+                Vec::new()
+            },
+        };
+        Ok((state, first_capture))
+    }
+
+    /// One step of the scroll loop: waits out a pause, checks the stop flag
+    /// and the scroll and time limits, scrolls, takes the next frame and runs
+    /// the end-of-content checks against `previous`, the last frame kept.
+    /// Returns `None`, after logging why, once the capture should stop.
+    fn scroll_step(
+        &self,
+        source: &dyn CaptureSource,
+        state: &mut ScrollState,
+        previous_capture: &RgbaImage,
+        logs: &Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>,
+    ) -> Result<Option<ScrollFrame>> {
+        let overlap = state.overlap;
+        let tolerance = self.pixel_tolerance;
+        let direction = self.direction;
+        let scroll_count = state.scroll_count;
+        let live = source.is_live();
+
+        self.wait_while_paused(&state.stop_flag, logs);
+
+        // Check stop flag
+        if let Some(flag) = &state.stop_flag
+            && *flag.lock().unwrap()
+        {
+            Self::log_event(
+                logs,
+                "Stopped by user",
+                LogEvent::Stopped {
+                    reason: StopReason::User,
+                },
+            );
+            return Ok(None);
+        }
+
+        if self.time_limit_reached(state.started, logs) {
+            return Ok(None);
+        }
+
+        // Check if we've reached max_scrolls limit
+        if let Some(max) = state.max_scrolls {
+            if scroll_count >= max {
+                Self::log_event(
+                    logs,
+                    &format!("Reached maximum scroll limit ({})", max),
+                    LogEvent::Stopped {
+                        reason: StopReason::MaxScrolls,
+                    },
                 );
-                None
+                return Ok(None);
             }
+            Self::log_event(
+                logs,
+                &format!(
+                    "[{}/{}] Pressing {}...",
+                    scroll_count + 1,
+                    max,
+                    state.key.to_string().to_uppercase()
+                ),
+                LogEvent::Scrolling {
+                    scroll: scroll_count + 1,
+                },
+            );
         } else {
-            None
-        };
+            Self::log_event(
+                logs,
+                &format!(
+                    "[{}] Pressing {}...",
+                    scroll_count + 1,
+                    state.key.to_string().to_uppercase()
+                ),
+                LogEvent::Scrolling {
+                    scroll: scroll_count + 1,
+                },
+            );
+        }
 
-        let mut images = Vec::new();
-        let first_capture = self.capture_screen(crop_region)?;
-        Self::log_msg(
-            &logs,
+        if let Some(enigo) = state.enigo.as_mut() {
+            self.scroll_down(enigo, &state.key, direction, state.wheel_clicks)?;
+        }
+
+        // Wait for content to settle after scrolling
+        let mut current_capture = match self.adaptive_delay.filter(|_| live) {
+            Some(max_settle_ms) => {
+                let settled = self.settle_until_stable(source, max_settle_ms)?;
+                if stitch::is_blank(&settled) {
+                    self.capture_non_blank(source, logs)?
+                } else {
+                    settled
+                }
+            }
+            None => {
+                if live {
+                    thread::sleep(Duration::from_millis(state.scroll_delay_ms));
+                }
+                self.capture_non_blank(source, logs)?
+            }
+        };
+        Self::ensure_not_empty(&current_capture, state.crop_region)?;
+        Self::log_event(
+            logs,
             &format!(
-                "Captured screen 1 ({}x{})",
-                first_capture.width(),
-                first_capture.height()
+                "Captured screen {} ({}x{})",
+                scroll_count + 2,
+                current_capture.width(),
+                current_capture.height()
             ),
+            LogEvent::Captured {
+                frame: scroll_count + 2,
+                w: current_capture.width(),
+                h: current_capture.height(),
+            },
         );
-        images.push(first_capture.clone());
+        self.dump_frame(&current_capture, scroll_count + 2, logs)?;
+        if self.animation.is_some() {
+            state.animation_frames.push(current_capture.clone());
+        }
 
-        let mut previous_capture = first_capture;
-        let mut scroll_count = 0;
+        self.notify_frame(&current_capture);
 
-        loop {
-            // Check stop flag
-            if let Some(ref flag) = stop_flag {
-                if *flag.lock().unwrap() {
-                    Self::log_msg(&logs, "Stopped by user");
-                    break;
+        // End-of-content checks only stop the capture after min_scrolls,
+        // and never with an exact frame count
+        let may_end = self.exact_frames.is_none() && scroll_count + 1 >= self.min_scrolls;
+
+        // Check if entire images are identical (no scrolling happened)
+        let mut is_identical = self.images_are_identical(previous_capture, &current_capture);
+
+        // The main key can get stuck where another still scrolls (Space
+        // at a fixed section, say), so the fallback key gets one try
+        // before an unchanged frame counts as the end
+        if is_identical
+            && may_end
+            && let Some(fallback_key) = &self.fallback_key
+        {
+            Self::log_msg(
+                logs,
+                &format!(
+                    "No movement, trying fallback key {}...",
+                    fallback_key.to_string().to_uppercase()
+                ),
+            );
+            if let Some(enigo) = state.enigo.as_mut() {
+                self.scroll_down(enigo, fallback_key, direction, state.wheel_clicks)?;
+                thread::sleep(Duration::from_millis(state.scroll_delay_ms));
+            }
+            let retry = self.capture_non_blank(source, logs)?;
+            if !self.images_are_identical(previous_capture, &retry) {
+                Self::log_msg(logs, "Fallback key moved the content, continuing");
+                self.dump_frame(&retry, scroll_count + 2, logs)?;
+                if self.animation.is_some() {
+                    state.animation_frames.push(retry.clone());
                 }
+                self.notify_frame(&retry);
+                current_capture = retry;
+                is_identical = false;
             }
+        }
 
-            // Check if we've reached max_scrolls limit
-            if let Some(max) = max_scrolls {
-                if scroll_count >= max {
-                    Self::log_msg(&logs, &format!("Reached maximum scroll limit ({})", max));
-                    break;
-                }
-                Self::log_msg(
-                    &logs,
+        if is_identical {
+            if may_end {
+                Self::log_event(
+                    logs,
+                    "Reached end of scrollable content (images are completely identical)",
+                    LogEvent::Stopped {
+                        reason: StopReason::Identical,
+                    },
+                );
+                return Ok(None);
+            }
+            let until = match self.exact_frames {
+                Some(frames) => format!("{} frames (--exact-frames)", frames),
+                None => format!("{} scrolls (--min-scrolls)", self.min_scrolls),
+            };
+            Self::log_msg(
+                logs,
+                &format!("Images are identical, continuing until {}", until),
+            );
+        }
+
+        // Horizontal frames are compared rotated, so the right strip is
+        // checked where the vertical code expects the bottom band
+        let previous_scan = direction.scan_view(previous_capture);
+        let current_scan = direction.scan_view(&current_capture);
+        let diff_percentage = stitch::images_are_similar_in_columns(
+            &previous_scan,
+            &current_scan,
+            overlap,
+            tolerance,
+            self.diff_threshold(),
+            stitch::centered_columns(previous_scan.width(), self.compare_width_fraction),
+        )
+        .1;
+
+        if self.adaptive_threshold
+            || tolerance > 0
+            || self.diff_threshold.is_some()
+            || self.metric != SimilarityMetric::ExactDiff
+        {
+            let (is_similar, diff_percentage) =
+                self.images_are_similar(&previous_scan, &current_scan, overlap, tolerance);
+
+            if is_similar && may_end {
+                Self::log_event(
+                    logs,
                     &format!(
-                        "[{}/{}] Pressing {}...",
-                        scroll_count + 1,
-                        max,
-                        key_type.to_uppercase()
+                        "Reached end of scrollable content (newly revealed band differs by only {:.4}%)",
+                        diff_percentage
                     ),
+                    LogEvent::Stopped {
+                        reason: StopReason::Similar,
+                    },
                 );
-            } else {
+                return Ok(None);
+            }
+        }
+
+        if self.end_on_content_only {
+            let (band_diffs, content_diffs) =
+                self.count_diffs_by_band(previous_capture, &current_capture);
+
+            if content_diffs == 0 && may_end {
+                Self::log_event(
+                    logs,
+                    &format!(
+                        "Reached end of scrollable content (only excluded bands changed: {} pixels)",
+                        band_diffs
+                    ),
+                    LogEvent::Stopped {
+                        reason: StopReason::ContentOnly,
+                    },
+                );
+                return Ok(None);
+            }
+        }
+
+        // Stall detection: animated content (clocks, ads, the cursor) keeps
+        // frames from ever being identical, so also stop once the newly
+        // revealed area stays nearly unchanged for several scrolls
+        let mut stalled = is_identical;
+        if self.stall_limit > 0 {
+            let revealed = current_scan.height().saturating_sub(overlap);
+            let (is_similar, diff_percentage) =
+                self.images_are_similar(&previous_scan, &current_scan, revealed, tolerance);
+
+            if is_similar {
+                stalled = true;
+                state.stall_count += 1;
                 Self::log_msg(
-                    &logs,
+                    logs,
                     &format!(
-                        "[{}] Pressing {}...",
-                        scroll_count + 1,
-                        key_type.to_uppercase()
+                        "No movement detected ({:.4}% changed), stall {}/{}",
+                        diff_percentage, state.stall_count, self.stall_limit
                     ),
                 );
+
+                if state.stall_count >= self.stall_limit && may_end {
+                    Self::log_event(
+                        logs,
+                        &format!(
+                            "Reached end of scrollable content (no movement for {} consecutive scrolls)",
+                            state.stall_count
+                        ),
+                        LogEvent::Stopped {
+                            reason: StopReason::Stalled,
+                        },
+                    );
+                    return Ok(None);
+                }
+            } else {
+                state.stall_count = 0;
             }
+        }
 
-            self.scroll_down(key_type)?;
+        state.scroll_count += 1;
+        Ok(Some(ScrollFrame {
+            image: current_capture,
+            stalled,
+            diff_percentage,
+        }))
+    }
 
-            // Wait for content to settle after scrolling
-            thread::sleep(Duration::from_millis(scroll_delay_ms));
+    /// The scroll loop: scrolls, takes the next frame from `source`, stops at
+    /// the end of the content and stitches. `crop_region` is only recorded in
+    /// the metadata and cursor trace.
+    fn capture_with_scroll_impl(
+        &self,
+        source: &dyn CaptureSource,
+        options: &CaptureOptions,
+        crop_region: Option<(i32, i32, i32, i32)>,
+        skip_input: bool,
+        stop_flag: Option<std::sync::Arc<std::sync::Mutex<bool>>>,
+        logs: Option<std::sync::Arc<std::sync::Mutex<Vec<String>>>>,
+    ) -> Result<RgbaImage> {
+        let tolerance = self.pixel_tolerance;
+        let direction = self.direction;
+        let stop_flag = stop_flag.or_else(|| self.stop_flag.clone());
+        let live = source.is_live();
+        let capture_time = chrono::Local::now();
 
-            let current_capture = self.capture_screen(crop_region)?;
-            Self::log_msg(
-                &logs,
-                &format!(
-                    "Captured screen {} ({}x{})",
-                    scroll_count + 2,
-                    current_capture.width(),
-                    current_capture.height()
-                ),
-            );
+        let (mut state, first_capture) =
+            self.start_scroll(source, options, crop_region, stop_flag.clone(), &logs)?;
+        let overlap = state.overlap;
+        let max_scrolls = state.max_scrolls;
 
-            // Check if entire images are identical (no scrolling happened)
-            let is_identical = self.images_are_identical(&previous_capture, &current_capture);
+        let mut images = vec![self.trim_first_frame(&first_capture, overlap, direction, &logs)?];
+        let mut frame_count = 1;
 
-            if is_identical {
-                Self::log_msg(
+        // Streamed captures keep only the last frame for the next overlap
+        let mut streamer = match &self.stream_output {
+            Some(_) if direction != ScrollDirection::Vertical => {
+                return Err(anyhow::anyhow!(
+                    "--stream-output only supports vertical scrolling"
+                ));
+            }
+            Some(path) if !self.dry_run => {
+                let mut streamer = stream::StreamingStitcher::create(path)?;
+                streamer.push(images[0].clone(), 0)?;
+                Some(streamer)
+            }
+            _ => None,
+        };
+        let mut cursor_samples = vec![self.sample_cursor()];
+
+        let mut overlaps: Vec<u32> = Vec::new();
+        let mut diff_percentages: Vec<f32> = Vec::new();
+
+        let mut previous_capture = first_capture;
+
+        // Terminal only; the GUI shows its own status, and the bar would mix
+        // into the JSON lines on stderr
+        let show_progress = !skip_input && !LOG_JSON.load(std::sync::atomic::Ordering::Relaxed);
+        let progress = show_progress.then(|| ProgressGuard::start(max_scrolls));
+        let raw_mode = (!skip_input).then(RawModeGuard::enable).transpose()?;
+
+        while let Some(step) = self.scroll_step(source, &mut state, &previous_capture, &logs)? {
+            let current_capture = step.image;
+            diff_percentages.push(step.diff_percentage);
+            let scroll_count = state.scroll_count;
+
+            // Stalled frames repeat the previous one, so they aren't stitched
+            // unless the frame count has to be exact
+            if !step.stalled || self.exact_frames.is_some() {
+                let previous_frame_scan = direction.scan_view(images.last().unwrap());
+                let current_scan = direction.scan_view(&current_capture);
+
+                if self.dry_run {
+                    let previous_scan = direction.scan_view(&previous_capture);
+                    let (_, diff_percentage) =
+                        self.images_are_similar(&previous_scan, &current_scan, overlap, tolerance);
+                    let detected = self
+                        .detect_overlap(&previous_frame_scan, &current_scan)
+                        .map(|px| format!("{}px", px))
+                        .unwrap_or_else(|| "none".to_string());
+                    Self::log_msg(
+                        &logs,
+                        &format!(
+                            "Pair {}-{}: {:.4}% of the last {}px changed, detected overlap {}",
+                            scroll_count,
+                            scroll_count + 1,
+                            diff_percentage,
+                            overlap,
+                            detected
+                        ),
+                    );
+                }
+
+                // A wrong scroll key or focus shows up on the first pair that
+                // moves; the sweeps are too slow to repeat for every frame, and
+                // are capped at half the frame like overlap detection
+                if overlaps.is_empty()
+                    && stitch::scrolled_backwards(
+                        &previous_frame_scan,
+                        &current_scan,
+                        previous_frame_scan.height().min(current_scan.height()) / 2,
+                    )
+                {
+                    Self::log_msg(
+                        &logs,
+                        "Warning: content appears to be scrolling up — check your scroll key/focus",
+                    );
+                }
+
+                overlaps.push(self.pair_overlap(
+                    &previous_frame_scan,
+                    &current_scan,
+                    overlap,
                     &logs,
-                    "Reached end of scrollable content (images are completely identical)",
-                );
-                break;
+                ));
+
+                frame_count += 1;
+                if let Some(streamer) = streamer.as_mut() {
+                    streamer.push(current_capture.clone(), *overlaps.last().unwrap())?;
+                    images.clear();
+                }
+                images.push(current_capture.clone());
+                cursor_samples.push(self.sample_cursor());
+                previous_capture = current_capture;
             }
 
-            images.push(current_capture.clone());
-            previous_capture = current_capture;
-            scroll_count += 1;
+            if let Some((_, bar)) = &progress {
+                bar.set_position(scroll_count as u64);
+            }
+            if let (Some(observer), Some(max)) = (&self.progress_observer, max_scrolls) {
+                observer((scroll_count as f32 / max.max(1) as f32).min(1.0));
+            }
 
             // Small delay before next scroll
-            thread::sleep(Duration::from_millis(timing::SMALL_DELAY_MS));
+            if live {
+                thread::sleep(Duration::from_millis(timing::SMALL_DELAY_MS));
+            }
 
             // Check for user input to stop early (only in terminal mode)
             if !skip_input {
@@ -721,32 +5431,312 @@ end tell
                             code: KeyCode::Char('q') | KeyCode::Char('Q'),
                             ..
                         }) => {
-                            Self::log_msg(&logs, "Stopped by user");
+                            Self::log_event(
+                                &logs,
+                                "Stopped by user",
+                                LogEvent::Stopped {
+                                    reason: StopReason::User,
+                                },
+                            );
+                            break;
+                        }
+                        // Raw mode delivers Ctrl+C as a key instead of SIGINT.
+                        // Raising the stop flag too lets a second Ctrl+C
+                        // during stitching reach the interrupt handler as
+                        // a repeat press.
+                        Event::Key(KeyEvent {
+                            code: KeyCode::Char('c'),
+                            modifiers,
+                            ..
+                        }) if modifiers.contains(KeyModifiers::CONTROL) => {
+                            if let Some(flag) = &stop_flag {
+                                *flag.lock().unwrap() = true;
+                            }
+                            Self::log_event(
+                                &logs,
+                                "Stopped with Ctrl+C, stitching the frames captured so far",
+                                LogEvent::Stopped {
+                                    reason: StopReason::User,
+                                },
+                            );
                             break;
                         }
                         _ => {} // Ignore other keys
                     }
                 }
-            } else {
+            } else if live {
                 // In GUI mode, just sleep
                 thread::sleep(Duration::from_millis(timing::KEYBOARD_POLL_MS));
             }
         }
 
-        // Clear any remaining events before finishing (only in terminal mode)
-        if !skip_input {
-            while poll(Duration::from_millis(0))? {
-                let _ = read();
+        drop(progress);
+        // Discards the keys pressed during the capture and leaves raw mode
+        drop(raw_mode);
+
+        if self.dry_run {
+            Self::log_msg(
+                &logs,
+                &format!(
+                    "Dry run complete: {} screens, {} scrolls (nothing stitched or saved)",
+                    frame_count, state.scroll_count
+                ),
+            );
+            return Ok(RgbaImage::new(0, 0));
+        }
+
+        if let Some((path, fps)) = &self.animation {
+            let saved = save_animation(&state.animation_frames, path, *fps)?;
+            Self::log_msg(
+                &logs,
+                &format!(
+                    "Saved animation ({} frames at {} fps) to {}",
+                    saved, fps, path
+                ),
+            );
+        }
+
+        *self.last_metadata.lock().unwrap() = Some(CaptureMetadata {
+            capture_time,
+            crop_region,
+            scroll_count: state.scroll_count,
+        });
+
+        if let Some(streamer) = streamer {
+            if self.sticky_agreement.is_some() || self.trace_cursor {
+                Self::log_msg(
+                    &logs,
+                    "Streamed output: skipping sticky band trimming and the cursor trace",
+                );
             }
+            Self::log_event(
+                &logs,
+                &format!("Writing {} streamed images...", frame_count),
+                LogEvent::Stitching {
+                    frames: frame_count,
+                },
+            );
+            let (width, height) = streamer.finish()?;
+            Self::log_event(
+                &logs,
+                &format!("Done! Final image: {}x{}", width, height),
+                LogEvent::Done {
+                    w: width,
+                    h: height,
+                },
+            );
+            *self.last_stats.lock().unwrap() = Some(CaptureStats {
+                frames: frame_count,
+                overlaps,
+                diff_percentages,
+                final_size: [width, height],
+                duration_ms: state.started.elapsed().as_millis() as u64,
+            });
+            return Ok(RgbaImage::new(0, 0));
         }
 
-        Self::log_msg(&logs, &format!("Stitching {} images...", images.len()));
-        let result = self.stitch_images(images, overlap);
-        Self::log_msg(
+        self.trim_sticky_bands(&mut images, &mut overlaps, direction, tolerance, &logs);
+
+        if self.trace_cursor {
+            self.record_cursor_trace(&images, &overlaps, crop_region, &cursor_samples, direction);
+        }
+
+        Self::log_event(
+            &logs,
+            &format!("Stitching {} images...", images.len()),
+            LogEvent::Stitching {
+                frames: images.len(),
+            },
+        );
+        let frames = images.len();
+        let result = self.stitch_images(images, &overlaps, direction);
+        Self::log_event(
             &logs,
             &format!("Done! Final image: {}x{}", result.width(), result.height()),
+            LogEvent::Done {
+                w: result.width(),
+                h: result.height(),
+            },
         );
 
+        *self.last_stats.lock().unwrap() = Some(CaptureStats {
+            frames,
+            overlaps,
+            diff_percentages,
+            final_size: [result.width(), result.height()],
+            duration_ms: state.started.elapsed().as_millis() as u64,
+        });
+
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_sized_crop_is_an_empty_frame() {
+        let error = ScreenCapture::new()
+            .resolve_crop_region(false, Some("0,0,1920,0".to_string()), &None)
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<CaptureError>(),
+            Some(CaptureError::EmptyFrame {
+                crop: Some((0, 0, 1920, 0))
+            })
+        ));
+    }
+
+    #[test]
+    fn scale_crop_region_doubles_logical_crop_on_retina() {
+        assert_eq!(
+            scale_crop_region((100, 100, 400, 300), 2.0),
+            (200, 200, 800, 600)
+        );
+    }
+
+    #[test]
+    fn window_rect_is_scaled_to_physical_pixels() {
+        // At 150% scaling (144 DPI) a DPI-unaware process sees a 1280x720
+        // window at (100, 50) for what the capture grabs as 1920x1080 at
+        // (150, 75); --window-only must crop the latter
+        assert_eq!(
+            scale_rect_for_dpi((100, 50, 1280, 720), 144),
+            (150, 75, 1920, 1080)
+        );
+        // Left of the primary monitor coordinates are negative
+        assert_eq!(
+            scale_rect_for_dpi((-1280, 0, 1280, 720), 144),
+            (-1920, 0, 1920, 1080)
+        );
+        assert_eq!(scale_rect_for_dpi((10, 20, 30, 40), 96), (10, 20, 30, 40));
+    }
+
+    #[test]
+    fn percent_crop_resolves_against_screen_size() {
+        use presets::CropSpec;
+
+        let spec = CropSpec::parse("10%,0%,80%,100%").unwrap();
+        assert_eq!(spec, CropSpec::Percent(10.0, 0.0, 80.0, 100.0));
+        assert_eq!(spec.resolve(1920, 1080), (192, 0, 1536, 1080));
+        assert_eq!(spec.resolve(2560, 1440), (256, 0, 2048, 1440));
+
+        assert_eq!(
+            CropSpec::parse("100,50,800,600"),
+            Some(CropSpec::Pixels(100, 50, 800, 600))
+        );
+        assert_eq!(CropSpec::parse("10%,0,80%,100%"), None);
+        assert_eq!(CropSpec::parse("0%,0%,120%,100%"), None);
+    }
+
+    #[test]
+    fn autotrim_removes_uniform_margins() {
+        let mut img = RgbaImage::from_pixel(100, 50, Rgba([240, 240, 240, 255]));
+        for y in 5..45 {
+            for x in 10..90 {
+                img.put_pixel(x, y, Rgba([(x * 2) as u8, y as u8, 0, 255]));
+            }
+        }
+        // Slight noise in the margin stays within the tolerance
+        img.put_pixel(3, 20, Rgba([236, 242, 240, 255]));
+
+        let trimmed = autotrim(&img, 8);
+        assert_eq!(trimmed.dimensions(), (80, 40));
+        assert_eq!(*trimmed.get_pixel(0, 0), Rgba([20, 5, 0, 255]));
+    }
+
+    #[test]
+    fn autotrim_leaves_mostly_blank_images_alone() {
+        let mut img = RgbaImage::from_pixel(100, 100, Rgba([255, 255, 255, 255]));
+        img.put_pixel(50, 50, Rgba([0, 0, 0, 255]));
+
+        assert_eq!(autotrim(&img, 0).dimensions(), (100, 100));
+    }
+
+    #[test]
+    fn scroll_keys_parse_their_names_and_map_to_keys() {
+        for key in ScrollKey::ALL {
+            assert_eq!(key.as_str().parse::<ScrollKey>(), Ok(key));
+        }
+        assert_eq!(" PageDown ".parse::<ScrollKey>(), Ok(ScrollKey::PageDown));
+        assert!("ctrl+down".parse::<ScrollKey>().is_err());
+        assert_eq!(
+            "pagedown".parse::<ScrollInput>(),
+            Ok(ScrollInput::Key(ScrollKey::PageDown))
+        );
+        assert!(matches!(
+            "ctrl+down".parse::<ScrollInput>(),
+            Ok(ScrollInput::Sequence { text, chords }) if text == "ctrl+down" && chords.len() == 1
+        ));
+        assert!("ctrl+nope".parse::<ScrollInput>().is_err());
+
+        assert_eq!(
+            ScrollKey::Down.as_key(ScrollDirection::Horizontal),
+            Some(Key::RightArrow)
+        );
+        assert_eq!(ScrollKey::Space.as_key(ScrollDirection::Horizontal), None);
+    }
+
+    #[test]
+    fn corners_snap_to_nearby_window_edges_only() {
+        let window = (100, 100, 400, 300);
+        assert_eq!(
+            snap_to_window_edges((95, 108), window, 12),
+            ((100, 100), true)
+        );
+        assert_eq!(
+            snap_to_window_edges((250, 392), window, 12),
+            ((250, 400), true)
+        );
+        assert_eq!(
+            snap_to_window_edges((250, 250), window, 12),
+            ((250, 250), false)
+        );
+        assert_eq!(
+            snap_to_window_edges((50, 105), window, 12),
+            ((50, 105), false)
+        );
+    }
+
+    #[test]
+    fn fit_crop_region_shrinks_negative_offsets() {
+        assert_eq!(
+            fit_crop_region((-50, -50, 100, 100), (100, 100)),
+            Some((0, 0, 50, 50))
+        );
+        assert_eq!(
+            fit_crop_region((-20, 10, 60, 30), (100, 100)),
+            Some((0, 10, 40, 30))
+        );
+    }
+
+    #[test]
+    fn fit_crop_region_rejects_regions_past_the_screen() {
+        // Shrinks to 150x150 at the origin, which still overflows 100x100
+        assert_eq!(fit_crop_region((-50, -50, 200, 200), (100, 100)), None);
+        // Entirely above and left of the screen
+        assert_eq!(fit_crop_region((-50, -50, 40, 40), (100, 100)), None);
+        assert_eq!(fit_crop_region((60, 0, 50, 50), (100, 100)), None);
+        assert_eq!(fit_crop_region((10, 20, 0, 5), (100, 100)), None);
+    }
+
+    #[test]
+    fn fit_crop_region_keeps_regions_that_fit() {
+        assert_eq!(
+            fit_crop_region((0, 0, 100, 100), (100, 100)),
+            Some((0, 0, 100, 100))
+        );
+    }
+
+    #[test]
+    fn tolerance_metric_sets_the_pixel_tolerance() {
+        let metric: SimilarityMetric = "tolerance:8".parse().unwrap();
+        assert_eq!(metric, SimilarityMetric::Tolerance(8));
+        assert_eq!(metric.to_string(), "tolerance:8");
+
+        let capture = ScreenCapture::new().with_metric(metric);
+        assert_eq!(capture.pixel_tolerance, 8);
+        assert_eq!(capture.metric, SimilarityMetric::ExactDiff);
+    }
+}
@@ -1,7 +1,28 @@
 use anyhow::Result;
-use capture::presets;
-use capture::{ScreenCapture, build_output_path, validate_format};
-use clap::Parser;
+use capture::{
+    CaptureOptions, ExcludedBands, OverlapSetting, ScreenCapture, ScrollDirection, ScrollInput,
+    SimilarityMetric, a4_page_height, autotrim, build_output_path, copy_to_clipboard,
+    draw_cursor_trace, is_lossy_format, next_available_path, save_as_pdf, save_image,
+    save_image_with_metadata, save_palette_png, scale_image, set_log_json, set_log_to_stderr,
+    set_verbosity, split_tall_image, to_luma, validate_animation_path, validate_format,
+};
+use capture::{ocr, presets};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser};
+use image::{DynamicImage, ImageFormat, RgbaImage};
+use std::sync::{Arc, Mutex};
+
+/// `println!` for messages, which move to stderr while --stdout writes the
+/// image to stdout
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if capture::log_to_stderr() {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
 
 #[derive(Parser, Debug)]
 #[command(name = "capture")]
@@ -10,6 +31,13 @@ struct Args {
     #[arg(long, help = "Launch GUI mode")]
     gui: bool,
 
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Custom presets file to read and write [default: $CAPTURE_PRESETS_FILE or ~/.capture-presets.json]"
+    )]
+    presets_file: Option<String>,
+
     #[arg(short, long, default_value = "00")]
     output: String,
 
@@ -24,10 +52,16 @@ struct Args {
     #[arg(
         short = 'p',
         long,
-        default_value_t = 125,
-        help = "Overlap pixels for stitching"
+        default_value = "125",
+        help = "Overlap pixels for stitching, a percentage of the frame height (e.g. '12%'), or 'auto' to detect it for each frame pair"
     )]
-    overlap: u32,
+    overlap: OverlapSetting,
+
+    #[arg(
+        long,
+        help = "Same as --overlap auto, keeping a fixed --overlap as the fallback"
+    )]
+    smart_overlap: bool,
 
     #[arg(
         short,
@@ -41,16 +75,69 @@ struct Args {
         short = 'k',
         long,
         default_value = "space",
-        help = "Key to use for scrolling: space, down, pagedown"
+        help = "Key to use for scrolling: space, down, pagedown, wheel, or a sequence like 'pagedown,pagedown' or 'ctrl+down'"
     )]
-    key: String,
+    key: ScrollInput,
+
+    #[arg(
+        long,
+        value_name = "CLICKS",
+        help = "Wheel clicks per scroll with --key wheel [default: one frame minus the overlap]"
+    )]
+    wheel_amount: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "KEY",
+        help = "Key tried once when a scroll doesn't move the content, before treating it as the end (e.g. pagedown)"
+    )]
+    fallback_key: Option<ScrollInput>,
+
+    #[arg(
+        long,
+        value_name = "FRACTION",
+        default_value_t = 1.0,
+        value_parser = parse_fraction,
+        help = "Only compare the centered FRACTION of the width when detecting the end (e.g. 0.2 on 4K)"
+    )]
+    compare_strip: f32,
+
+    #[arg(
+        long,
+        value_name = "METRIC",
+        default_value = "exact",
+        help = "Frame comparison: exact, tolerance:N (same as --pixel-tolerance N) or ssim[:THRESHOLD] (structural, default 0.98)"
+    )]
+    metric: SimilarityMetric,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = ScrollDirection::Vertical,
+        help = "Scroll and stitch direction (horizontal: down = Right arrow, others = horizontal wheel)"
+    )]
+    direction: ScrollDirection,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "Index of the display to capture (0 = first)"
+    )]
+    display: usize,
 
     #[arg(long, help = "Capture only the focused window (not full screen)")]
     window_only: bool,
 
     #[arg(
         long,
-        help = "Manual crop region as 'x,y,width,height' (e.g., '100,50,1920,1080')"
+        value_name = "SUBSTR",
+        help = "Capture the window whose title (app name on macOS) contains SUBSTR, case-insensitive; overrides --window-only"
+    )]
+    window_title: Option<String>,
+
+    #[arg(
+        long,
+        help = "Manual crop region as 'x,y,width,height' in pixels or percent of the screen (e.g., '100,50,1920,1080' or '10%,0%,80%,100%')"
     )]
     crop: Option<String>,
 
@@ -60,18 +147,150 @@ struct Args {
     )]
     crop_preset: Option<String>,
 
-    #[arg(long, help = "Interactive mode: select crop region with mouse")]
+    #[arg(
+        long,
+        help = "Abort if the --window-only bounds don't fit the screen instead of capturing full screen (--crop is always checked)"
+    )]
+    strict_crop: bool,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "Pixels to trim from the top of the first frame only (e.g. window title bar)"
+    )]
+    trim_first_top: u32,
+
+    #[arg(
+        long,
+        value_name = "COLORS",
+        help = "Quantize the result to an indexed PNG with this many colors (2-256)"
+    )]
+    palette: Option<usize>,
+
+    #[arg(
+        long,
+        default_value_t = capture::constants::defaults::QUALITY,
+        value_parser = clap::value_parser!(u8).range(0..=100),
+        help = "Encoding quality for jpg/webp output (0-100)"
+    )]
+    quality: u8,
+
+    #[arg(
+        long,
+        value_name = "PIXELS",
+        help = "Height of each PDF page slice in pixels [default: A4 aspect of the image width]"
+    )]
+    pdf_page_height: Option<u32>,
+
+    #[arg(long, help = "Capture, compare and save in grayscale")]
+    grayscale: bool,
+
+    #[arg(
+        long,
+        value_name = "0-255",
+        help = "Save bilevel: luma above the cutoff becomes white, the rest black"
+    )]
+    threshold: Option<u8>,
+
+    #[arg(
+        long,
+        help = "End capture when frames differ only inside the --ignore-* bands"
+    )]
+    end_on_content_only: bool,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        requires = "end_on_content_only",
+        help = "Rows at the top excluded from end-of-content detection (sticky header)"
+    )]
+    ignore_top: u32,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        requires = "end_on_content_only",
+        help = "Rows at the bottom excluded from end-of-content detection (sticky footer)"
+    )]
+    ignore_bottom: u32,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        requires = "end_on_content_only",
+        help = "Columns at the right excluded from end-of-content detection (scrollbar)"
+    )]
+    ignore_right: u32,
+
+    #[arg(
+        long,
+        help = "Record the cursor at each frame and save an annotated copy (<output>_trace)"
+    )]
+    trace_cursor: bool,
+
+    #[arg(
+        long,
+        help = "End capture when the newly revealed band stops changing, with a diff threshold tuned to the content"
+    )]
+    adaptive_threshold: bool,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "Per-channel difference still treated as equal when comparing frames (0 = exact)"
+    )]
+    pixel_tolerance: u8,
+
+    #[arg(
+        long,
+        help = "Interactive mode: drag out the crop region on a screen overlay"
+    )]
     select_region: bool,
 
+    #[arg(
+        long,
+        requires = "select_region",
+        help = "Snap selected corners near the focused window's edges onto them"
+    )]
+    snap_to_window: bool,
+
     #[arg(long, help = "List available crop presets")]
     list_presets: bool,
 
     #[arg(
         long,
-        help = "Save current crop region as a preset: 'name:x,y,width,height'"
+        help = "Save a crop region as a preset: 'name:x,y,width,height' (also stores --overlap, --key and --scroll-delay when given)"
     )]
     save_preset: Option<String>,
 
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Grab one frame with a preset's crop and open a thumbnail of it, to check the region"
+    )]
+    preview_preset: Option<String>,
+
+    // Video mode options
+    #[arg(
+        long,
+        help = "Record frames while auto-scrolling, then stitch the unique ones"
+    )]
+    video: bool,
+
+    #[arg(
+        long,
+        default_value_t = 10,
+        help = "Recording duration in seconds (video mode only)"
+    )]
+    duration: u64,
+
+    #[arg(
+        long,
+        default_value_t = 2,
+        help = "Frames to capture per second in video mode, playback rate of --animate"
+    )]
+    fps: u32,
+
     // Old mode options
     #[arg(
         short,
@@ -86,46 +305,399 @@ struct Args {
         help = "Delay in milliseconds after scrolling before capturing (screenshot mode only)"
     )]
     scroll_delay: u64,
+
+    // Item list mode options
+    #[arg(
+        long,
+        requires = "item_count",
+        help = "Height in pixels of each list item, at most half the frame (item list mode)"
+    )]
+    item_height: Option<u32>,
+
+    #[arg(
+        long,
+        requires = "item_height",
+        help = "Number of items in the list (item list mode)"
+    )]
+    item_count: Option<usize>,
+
+    // Timelapse mode options
+    #[arg(
+        long,
+        conflicts_with_all = ["video", "item_height", "targets", "dry_run"],
+        help = "Capture the region every --interval ms, --count times, without scrolling, stacked top to bottom"
+    )]
+    timelapse: bool,
+
+    #[arg(
+        long,
+        value_name = "MS",
+        default_value_t = capture::constants::defaults::TIMELAPSE_INTERVAL_MS,
+        requires = "timelapse",
+        help = "Milliseconds between timelapse frames"
+    )]
+    interval: u64,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = capture::constants::defaults::TIMELAPSE_COUNT,
+        requires = "timelapse",
+        help = "Number of timelapse frames"
+    )]
+    count: usize,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Capture each window listed (one title per line) in sequence"
+    )]
+    targets: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Stitch the PNG frames in DIR (in file name order) instead of capturing"
+    )]
+    stitch_dir: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["video", "stitch_dir", "targets"],
+        help = "Stitch a recorded video FILE (decoded at --fps with ffmpeg) instead of capturing"
+    )]
+    from_video: Option<String>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["video", "item_height", "targets"],
+        help = "Run the scroll loop and print per-pair stats without stitching or saving"
+    )]
+    dry_run: bool,
+
+    #[arg(
+        long,
+        help = "Cross-fade frames across the overlap instead of a hard cut at its middle"
+    )]
+    seam_blend: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "video", "item_height", "timelapse", "targets", "stitch_dir", "from_video",
+            "seam_blend", "trim_sticky", "trace_cursor", "animate", "autotrim", "threshold",
+            "max_height", "clipboard", "ocr", "scale",
+        ],
+        help = "Write the page to the PNG output as it is stitched, for captures too long to hold in memory"
+    )]
+    stream_output: bool,
+
+    #[arg(
+        long,
+        default_value_t = capture::constants::defaults::STALL_LIMIT,
+        help = "End after this many consecutive scrolls without visible movement (0 = off)"
+    )]
+    stall_limit: usize,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Save every captured frame into DIR as frame_0001.png, frame_0002.png, ..."
+    )]
+    dump_frames: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["video", "item_height"],
+        help = "Also save the raw scroll sequence as an animation (.apng or .webp) at --fps"
+    )]
+    animate: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        help = "End once at most PERCENT of revealed pixels change between frames (higher tolerates more motion; 0.5 when other options compare frames)"
+    )]
+    diff_threshold: Option<f32>,
+
+    #[arg(
+        long,
+        help = "Detect a fixed header/footer repeated in every frame and keep only one copy"
+    )]
+    trim_sticky: bool,
+
+    #[arg(
+        long,
+        value_name = "FRACTION",
+        default_value_t = capture::constants::sticky::DEFAULT_AGREEMENT,
+        value_parser = parse_fraction,
+        help = "Fraction of frame pairs a row must stay unchanged in to count as sticky (with --trim-sticky)"
+    )]
+    sticky_agreement: f32,
+
+    #[arg(
+        long,
+        default_value_t = 0,
+        help = "Scrolls to perform before end-of-page detection may stop the capture"
+    )]
+    min_scrolls: usize,
+
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+        conflicts_with_all = [
+            "max_scrolls", "min_scrolls", "video", "timelapse", "item_height", "stitch_dir",
+            "from_video",
+        ],
+        help = "Capture and stitch exactly N frames, ignoring end-of-page detection"
+    )]
+    exact_frames: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "PIXELS",
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Split results taller than this into <output>_001, <output>_002, ... overlapping by 100px"
+    )]
+    max_height: Option<u32>,
+
+    #[arg(
+        long,
+        help = "Crop uniform-colored margins (window padding, chrome) from the edges of the result"
+    )]
+    autotrim: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = capture::constants::autotrim::DEFAULT_TOLERANCE,
+        requires = "autotrim",
+        help = "Channel difference still treated as margin color with --autotrim"
+    )]
+    autotrim_tolerance: u8,
+
+    #[arg(
+        long,
+        value_name = "FACTOR",
+        default_value_t = capture::constants::defaults::SCALE,
+        value_parser = parse_scale,
+        help = "Resize the final image by FACTOR (e.g. 0.5 for half size) with Lanczos3 before saving"
+    )]
+    scale: f32,
+
+    #[arg(
+        long,
+        help = "If the output file exists, save as 'name (1).ext', 'name (2).ext', ... instead of overwriting"
+    )]
+    auto_name: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "select_region", "targets", "max_height", "stream_output", "palette", "dry_run",
+            "auto_name",
+        ],
+        help = "Write the PNG to stdout instead of a file, for piping; messages go to stderr"
+    )]
+    stdout: bool,
+
+    #[arg(long, help = "Also copy the stitched image to the clipboard")]
+    clipboard: bool,
+
+    #[arg(
+        long,
+        help = "Extract the stitched image's text into a .txt file next to it (needs --features ocr)"
+    )]
+    ocr: bool,
+
+    #[arg(
+        long,
+        value_name = "LANG",
+        default_value = capture::constants::ocr::DEFAULT_LANG,
+        requires = "ocr",
+        help = "Tesseract language code(s) for --ocr, e.g. 'kor' or 'jpn+eng'"
+    )]
+    ocr_lang: String,
+
+    #[arg(
+        long,
+        help = "Scroll to the top (Home, or Cmd+Up on macOS) before the first capture"
+    )]
+    home_first: bool,
+
+    #[arg(
+        long,
+        help = "Instead of --scroll-delay, wait after each scroll until the screen stops changing"
+    )]
+    adaptive_delay: bool,
+
+    #[arg(
+        long,
+        value_name = "MS",
+        default_value_t = capture::constants::timing::MAX_SETTLE_MS,
+        requires = "adaptive_delay",
+        help = "Longest wait in milliseconds for the screen to settle with --adaptive-delay"
+    )]
+    max_settle: u64,
+
+    #[arg(long, help = "Keep the mouse cursor out of the captured frames")]
+    hide_cursor: bool,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Stop scrolling (or a timelapse) after this many seconds and save what was captured"
+    )]
+    max_duration: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Also write frame count, overlaps, diff percentages and timing to <output>.json"
+    )]
+    stats_json: bool,
+
+    #[arg(
+        long,
+        help = "Write capture progress as newline-delimited JSON events to stderr"
+    )]
+    log_json: bool,
+
+    #[arg(
+        long,
+        alias = "quiet-permissions",
+        help = "Skip the startup check for mouse, screen capture and input permissions"
+    )]
+    skip_permission_check: bool,
+
+    #[arg(
+        short,
+        long,
+        action = clap::ArgAction::Count,
+        help = "Print [DEBUG] diagnostics: -v for retries and per-frame results, -vv for every comparison"
+    )]
+    verbose: u8,
+}
+
+fn parse_fraction(s: &str) -> Result<f32, String> {
+    let value: f32 = s.parse().map_err(|_| format!("'{}' is not a number", s))?;
+    if (0.0..=1.0).contains(&value) {
+        Ok(value)
+    } else {
+        Err(format!("{} is not between 0.0 and 1.0", value))
+    }
+}
+
+fn parse_scale(s: &str) -> Result<f32, String> {
+    let value: f32 = s.parse().map_err(|_| format!("'{}' is not a number", s))?;
+    if value.is_finite() && value > 0.0 {
+        Ok(value)
+    } else {
+        Err(format!("{} is not a positive scale factor", value))
+    }
 }
 
 fn list_presets() -> Result<()> {
-    println!("\nAVAILABLE CROP PRESETS");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    say!("\nAVAILABLE CROP PRESETS");
+    say!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
     let builtin = presets::get_builtin_presets();
     let custom = presets::load_presets()?;
 
-    println!("\nBuilt-in presets:");
+    say!("\nBuilt-in presets:");
     for (name, value) in builtin.iter() {
         if !custom.contains_key(name) {
-            println!("  {} = {}", name, value);
+            say!("  {} = {}", name, value);
         }
     }
 
     if !custom.is_empty() {
-        println!("\nCustom presets:");
+        say!("\nCustom presets:");
         for (name, value) in custom.iter() {
-            println!("  {} = {}", name, value);
+            say!("  {} = {}", name, value);
         }
 
         let preset_file = presets::get_preset_file_path()?;
-        println!("\nCustom presets file: {}", preset_file.display());
+        say!("\nCustom presets file: {}", preset_file.display());
     } else {
-        println!("\nCustom presets: (none)");
+        say!("\nCustom presets: (none)");
         let preset_file = presets::get_preset_file_path()?;
-        println!("   Save presets with: --save-preset name:x,y,w,h");
-        println!("   File will be created at: {}", preset_file.display());
+        say!("   Save presets with: --save-preset name:x,y,w,h");
+        say!("   File will be created at: {}", preset_file.display());
+    }
+
+    say!("\nUsage:");
+    say!("   --crop-preset <name>");
+    say!("   Example: --crop-preset 1080p");
+    say!();
+
+    Ok(())
+}
+
+/// Captures the preset's crop once and saves a thumbnail to the temp
+/// directory, opening it in the default viewer when possible
+fn preview_preset(name: &str, display: usize) -> Result<()> {
+    let all_presets = presets::get_all_presets()?;
+    let Some(preset) = all_presets.get(name) else {
+        return Err(anyhow::anyhow!(
+            "Preset '{}' not found. Use --list-presets to see available presets.",
+            name
+        ));
+    };
+
+    let capture = ScreenCapture::new().with_display(display);
+    let frame = capture.capture_still(Some(preset.crop.clone()))?;
+    let size = capture::constants::defaults::PREVIEW_THUMBNAIL_SIZE;
+    let (width, height) = frame.dimensions();
+    let scale = (size as f32 / width.max(height) as f32).min(1.0);
+    let thumbnail = image::imageops::thumbnail(
+        &frame,
+        ((width as f32 * scale).round() as u32).max(1),
+        ((height as f32 * scale).round() as u32).max(1),
+    );
+
+    let path = std::env::temp_dir().join(format!("capture-preview-{}.png", name));
+    thumbnail.save(&path)?;
+    say!(
+        "Preset '{}' ({}): {}x{} frame, thumbnail saved to {}",
+        name,
+        preset.crop,
+        width,
+        height,
+        path.display()
+    );
+
+    if let Err(e) = open_in_viewer(&path) {
+        say!("Could not open the thumbnail ({}); open it manually", e);
     }
+    Ok(())
+}
 
-    println!("\nUsage:");
-    println!("   --crop-preset <name>");
-    println!("   Example: --crop-preset 1080p");
-    println!();
+/// Opens `path` with the platform's default application
+fn open_in_viewer(path: &std::path::Path) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    let mut command = std::process::Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(target_os = "linux")]
+    let mut command = std::process::Command::new("xdg-open");
 
+    command.arg(path).spawn()?;
     Ok(())
 }
 
-fn save_preset_from_string(preset_str: &str) -> Result<()> {
+/// Whether the user passed `id` on the command line rather than getting its default
+fn given_on_command_line(matches: &ArgMatches, id: &str) -> bool {
+    matches.value_source(id) == Some(ValueSource::CommandLine)
+}
+
+fn save_preset_from_string(preset_str: &str, args: &Args, matches: &ArgMatches) -> Result<()> {
     let parts: Vec<&str> = preset_str.splitn(2, ':').collect();
 
     if parts.len() != 2 {
@@ -138,25 +710,388 @@ fn save_preset_from_string(preset_str: &str) -> Result<()> {
     let value = parts[1].trim();
 
     // Validate the crop region format
-    if presets::parse_crop_region(value).is_none() {
+    if !presets::is_valid_crop_region(value) {
         return Err(anyhow::anyhow!(
-            "Invalid crop region format: {}\nUse: x,y,width,height (e.g., '100,50,1920,1080')",
+            "Invalid crop region format: {}\nUse: x,y,width,height (e.g., '100,50,1920,1080' or '0,0,full,full')",
             value
         ));
     }
 
+    let mut preset = presets::Preset::from_crop(value);
+    if given_on_command_line(matches, "overlap") {
+        match args.overlap {
+            OverlapSetting::Fixed(pixels) => preset.overlap = Some(pixels),
+            OverlapSetting::Auto => {
+                return Err(anyhow::anyhow!(
+                    "Presets store a fixed overlap; --overlap auto can't be saved"
+                ));
+            }
+            OverlapSetting::Percent(_) => {
+                return Err(anyhow::anyhow!(
+                    "Presets store a fixed overlap; a percentage overlap can't be saved"
+                ));
+            }
+        }
+    }
+    if given_on_command_line(matches, "key") {
+        preset.key = Some(args.key.to_string());
+    }
+    if given_on_command_line(matches, "scroll_delay") {
+        preset.scroll_delay = Some(args.scroll_delay);
+    }
+
     let mut preset_map = presets::load_presets()?;
-    preset_map.insert(name.to_string(), value.to_string());
+    preset_map.insert(name.to_string(), preset.clone());
     presets::save_presets(&preset_map)?;
 
-    println!("Preset '{}' saved: {}", name, value);
-    println!("\nUse with: --crop-preset {}", name);
+    say!("Preset '{}' saved: {}", name, preset);
+    say!("\nUse with: --crop-preset {}", name);
+
+    Ok(())
+}
+
+/// Saves the result with all the output options applied and returns the
+/// path it was written to, which differs from `output_path` with --auto-name
+fn save_output(
+    image: &RgbaImage,
+    output_path: &str,
+    args: &Args,
+    capture: &ScreenCapture,
+) -> Result<String> {
+    // --stream-output wrote the image during the capture
+    if args.stream_output {
+        if args.stats_json {
+            save_stats_json(output_path, capture)?;
+        }
+        return Ok(output_path.to_string());
+    }
+
+    let output_path = if args.auto_name {
+        next_available_path(std::path::Path::new(output_path))
+            .to_string_lossy()
+            .into_owned()
+    } else {
+        output_path.to_string()
+    };
+    let output_path = output_path.as_str();
+
+    let trimmed = args
+        .autotrim
+        .then(|| autotrim(image, args.autotrim_tolerance));
+    if let Some(trimmed) = &trimmed {
+        say!(
+            "Trimmed margins: {}x{} -> {}x{}",
+            image.width(),
+            image.height(),
+            trimmed.width(),
+            trimmed.height()
+        );
+    }
+    let image = trimmed.as_ref().unwrap_or(image);
+
+    // Scaling the stitched image once is cheaper and sharper than scaling
+    // every frame
+    let scaled = (args.scale != 1.0).then(|| scale_image(image, args.scale));
+    if let Some(scaled) = &scaled {
+        say!(
+            "Scaled by {}: {}x{} -> {}x{}",
+            args.scale,
+            image.width(),
+            image.height(),
+            scaled.width(),
+            scaled.height()
+        );
+    }
+    let image = scaled.as_ref().unwrap_or(image);
+
+    // Tone conversion runs on the stitched image, before any encoder
+    let toned = (args.grayscale || args.threshold.is_some())
+        .then(|| DynamicImage::ImageLuma8(to_luma(image, args.threshold)).to_rgba8());
+    let image = toned.as_ref().unwrap_or(image);
+
+    match args
+        .max_height
+        .filter(|&max_height| image.height() > max_height)
+    {
+        _ if args.stdout => write_png_to_stdout(image)?,
+        Some(max_height) => {
+            let parts = split_tall_image(image, max_height, capture::constants::split::SEAM_HEIGHT);
+            for (index, part) in parts.iter().enumerate() {
+                let part_path = numbered_output_path(output_path, index + 1);
+                encode_output(part, &part_path, args, capture)?;
+                say!("Part {}/{}: {}", index + 1, parts.len(), part_path);
+            }
+        }
+        None => encode_output(image, output_path, args, capture)?,
+    }
+
+    if args.clipboard {
+        if cfg!(target_os = "linux") {
+            say!(
+                "Copied to clipboard, waiting until something else is copied (it's cleared when this exits)"
+            );
+        }
+        copy_to_clipboard(image, true)?;
+        if !cfg!(target_os = "linux") {
+            say!("Copied to clipboard");
+        }
+    }
+
+    if args.ocr {
+        let text = ocr::recognize_text(image, &args.ocr_lang)?;
+        let text_path = std::path::Path::new(output_path).with_extension("txt");
+        std::fs::write(&text_path, &text)?;
+        say!(
+            "OCR text ({} lines) saved to {}",
+            text.lines().filter(|line| !line.trim().is_empty()).count(),
+            text_path.display()
+        );
+    }
+
+    if args.stats_json {
+        save_stats_json(output_path, capture)?;
+    }
+    if args.stdout {
+        return Ok("stdout".to_string());
+    }
+    Ok(output_path.to_string())
+}
+
+/// Encodes the image as PNG in memory and writes it to stdout for --stdout
+fn write_png_to_stdout(image: &RgbaImage) -> Result<()> {
+    use std::io::Write;
+
+    let mut encoded = std::io::Cursor::new(Vec::new());
+    image.write_to(&mut encoded, ImageFormat::Png)?;
+
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(encoded.get_ref())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Writes the last capture's stats next to the output for --stats-json
+fn save_stats_json(output_path: &str, capture: &ScreenCapture) -> Result<()> {
+    match capture.last_capture_stats() {
+        Some(stats) => {
+            let stats_path = std::path::Path::new(output_path).with_extension("json");
+            std::fs::write(&stats_path, serde_json::to_string_pretty(&stats)?)?;
+            say!("Capture stats saved to {}", stats_path.display());
+        }
+        None => say!("No capture stats in this mode, --stats-json skipped"),
+    }
+    Ok(())
+}
+
+/// `out.png` -> `out_001.png` for the parts of a --max-height split
+fn numbered_output_path(output_path: &str, number: usize) -> String {
+    let path = std::path::Path::new(output_path);
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let file_name = match path.extension().and_then(|e| e.to_str()) {
+        Some(extension) => format!("{}_{:03}.{}", stem, number, extension),
+        None => format!("{}_{:03}", stem, number),
+    };
+    path.with_file_name(file_name)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Writes one image with the encoder the output options ask for
+fn encode_output(
+    image: &RgbaImage,
+    output_path: &str,
+    args: &Args,
+    capture: &ScreenCapture,
+) -> Result<()> {
+    if args
+        .format
+        .trim_start_matches('.')
+        .eq_ignore_ascii_case("pdf")
+    {
+        let page_height = args
+            .pdf_page_height
+            .unwrap_or_else(|| a4_page_height(image.width()));
+        save_as_pdf(image, output_path, page_height)?;
+        say!(
+            "PDF: {} page(s) of {}px",
+            image.height().div_ceil(page_height.max(1)),
+            page_height
+        );
+    } else if let Some(colors) = args.palette {
+        // Encode the truecolor version in memory to report the savings
+        let mut truecolor = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut truecolor, ImageFormat::Png)?;
+
+        save_palette_png(image, output_path, colors)?;
+
+        let indexed_size = std::fs::metadata(output_path)?.len();
+        say!(
+            "Palette: {} colors, {} KB -> {} KB",
+            colors,
+            truecolor.get_ref().len() / 1024,
+            indexed_size / 1024
+        );
+    } else if (args.grayscale || args.threshold.is_some()) && !is_lossy_format(&args.format) {
+        // The image is already gray, so drop the redundant channels on save
+        DynamicImage::ImageRgba8(image.clone())
+            .to_luma8()
+            .save(output_path)?;
+    } else if let Some(metadata) = capture.last_capture_metadata().filter(|_| {
+        args.format
+            .trim_start_matches('.')
+            .eq_ignore_ascii_case("png")
+    }) {
+        save_image_with_metadata(image, output_path, &metadata)?;
+    } else {
+        save_image(image, output_path, args.quality)?;
+    }
 
     Ok(())
 }
 
+/// Turns a window title into a filename-safe suffix
+fn title_to_filename(title: &str) -> String {
+    let slug: String = title
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    slug.trim_matches('_').to_string()
+}
+
+/// Routes Ctrl+C to the capture's stop flag so the loop ends and the frames
+/// captured so far are stitched and saved. A second Ctrl+C exits right away.
+fn install_interrupt_handler(stop_flag: Arc<Mutex<bool>>) -> Result<()> {
+    ctrlc::set_handler(move || {
+        let mut stop = stop_flag.lock().unwrap();
+        if *stop {
+            eprintln!("\nInterrupted again, exiting without saving");
+            std::process::exit(130);
+        }
+        *stop = true;
+        eprintln!(
+            "\nCtrl+C: finishing the current frame and saving what was captured (press again to quit)"
+        );
+    })
+    .map_err(|e| anyhow::anyhow!("Failed to install Ctrl+C handler: {}", e))
+}
+
+fn run_targets(
+    capture: &ScreenCapture,
+    targets_file: &str,
+    args: &Args,
+    stop_flag: &Mutex<bool>,
+) -> Result<()> {
+    let content = std::fs::read_to_string(targets_file)
+        .map_err(|e| anyhow::anyhow!("Failed to read targets file '{}': {}", targets_file, e))?;
+    let titles: Vec<&str> = content
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+
+    if titles.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No window titles found in '{}'",
+            targets_file
+        ));
+    }
+
+    say!("🗂  BATCH MODE: {} targets", titles.len());
+    say!();
+
+    let original_focus = capture.focused_window();
+    let mut results: Vec<(&str, Result<String>)> = Vec::new();
+
+    for (i, title) in titles.iter().enumerate() {
+        say!("[{}/{}] Target '{}'", i + 1, titles.len(), title);
+
+        let result = match capture.activate_window_by_title(title) {
+            Ok(Some((x, y, w, h))) => {
+                let output_path = build_output_path(
+                    &format!("{}_{}", args.output, title_to_filename(title)),
+                    &args.format,
+                );
+
+                // Bounds come from the raised window
+                let crop = Some(format!("{},{},{},{}", x, y, w, h));
+                capture
+                    .capture_with_scroll(&capture_options(args, false, crop))
+                    .map_err(anyhow::Error::from)
+                    .and_then(|image| save_output(&image, &output_path, args, capture))
+            }
+            Ok(None) => Err(anyhow::anyhow!("No window matching '{}'", title)),
+            Err(e) => Err(e.into()),
+        };
+
+        match &result {
+            Ok(path) => say!("Saved to {}", path),
+            Err(e) => say!("Skipped: {}", e),
+        }
+        say!();
+        results.push((title, result));
+
+        if *stop_flag.lock().unwrap() {
+            say!("Interrupted, skipping the remaining targets");
+            say!();
+            break;
+        }
+    }
+
+    if let Some(window) = &original_focus {
+        capture.restore_focus(window)?;
+    }
+
+    say!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    say!("Batch summary:");
+    for (title, result) in &results {
+        match result {
+            Ok(path) => say!("  ✓ {} -> {}", title, path),
+            Err(e) => say!("  ✗ {}: {}", title, e),
+        }
+    }
+
+    let succeeded = results.iter().filter(|(_, r)| r.is_ok()).count();
+    say!("{}/{} targets captured", succeeded, results.len());
+
+    Ok(())
+}
+
+/// Capture options from the command line, for `crop` (or the focused window
+/// with `window_only`)
+fn capture_options(args: &Args, window_only: bool, crop: Option<String>) -> CaptureOptions {
+    CaptureOptions::new()
+        .with_overlap(args.overlap.pixels())
+        .with_max_scrolls(args.max_scrolls)
+        .with_delay(args.delay)
+        .with_key(args.key.clone())
+        .with_window_only(window_only)
+        .with_crop(crop)
+        .with_scroll_delay(args.scroll_delay)
+}
+
+fn save_cursor_trace(capture: &ScreenCapture, image: &RgbaImage, args: &Args) -> Result<()> {
+    let trace = capture.cursor_trace();
+    let trace_path = build_output_path(&format!("{}_trace", args.output), &args.format);
+
+    draw_cursor_trace(image, &trace).save(&trace_path)?;
+    say!(
+        "Saved cursor trace ({} positions) to {}",
+        trace.len(),
+        trace_path
+    );
+    Ok(())
+}
+
 fn main() -> Result<()> {
-    let args = Args::parse();
+    capture::enable_dpi_awareness();
+    let matches = Args::command().get_matches();
+    let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    set_log_to_stderr(args.stdout);
+    presets::set_preset_file_path(args.presets_file.as_ref().map(std::path::PathBuf::from));
 
     // Launch GUI mode if requested
     if args.gui {
@@ -171,39 +1106,177 @@ fn main() -> Result<()> {
 
     // Handle --save-preset
     if let Some(preset_str) = &args.save_preset {
-        return save_preset_from_string(preset_str);
+        return save_preset_from_string(preset_str, &args, &matches);
+    }
+
+    // Handle --preview-preset
+    if let Some(name) = &args.preview_preset {
+        return preview_preset(name, args.display);
+    }
+
+    // Resolve the preset first: its scroll settings apply wherever the
+    // matching flag wasn't given explicitly (preset crop takes precedence
+    // over --crop if both are specified)
+    let preset = match &args.crop_preset {
+        Some(preset_name) => {
+            let all_presets = presets::get_all_presets()?;
+            match all_presets.get(preset_name) {
+                Some(preset) => {
+                    say!("Using preset '{}': {}", preset_name, preset);
+                    Some(preset.clone())
+                }
+                None => {
+                    return Err(anyhow::anyhow!(
+                        "Preset '{}' not found. Use --list-presets to see available presets.",
+                        preset_name
+                    ));
+                }
+            }
+        }
+        None => None,
+    };
+    if let Some(preset) = &preset {
+        if let Some(overlap) = preset.overlap
+            && !given_on_command_line(&matches, "overlap")
+        {
+            args.overlap = OverlapSetting::Fixed(overlap);
+        }
+        if let Some(key) = &preset.key
+            && !given_on_command_line(&matches, "key")
+        {
+            args.key = key.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+        }
+        if let Some(scroll_delay) = preset.scroll_delay
+            && !given_on_command_line(&matches, "scroll_delay")
+        {
+            args.scroll_delay = scroll_delay;
+        }
     }
 
+    set_log_json(args.log_json);
+    set_verbosity(args.verbose);
+
     // Validate format before starting capture
     validate_format(&args.format)?;
+    if args.ocr && !ocr::is_available() {
+        return Err(anyhow::anyhow!(
+            "--ocr needs OCR support; rebuild with `cargo build --features ocr`"
+        ));
+    }
+    if let Some(path) = &args.animate {
+        validate_animation_path(path)?;
+    }
+    if args.palette.is_some() && args.format.trim_start_matches('.').to_lowercase() != "png" {
+        return Err(anyhow::anyhow!("--palette requires png output format"));
+    }
+    if args.scale > 1.0 {
+        say!(
+            "Warning: --scale {} enlarges the image, which adds size but no detail",
+            args.scale
+        );
+    }
+    if args.stdout && args.format.trim_start_matches('.').to_lowercase() != "png" {
+        return Err(anyhow::anyhow!("--stdout requires png output format"));
+    }
+    if args.stream_output && args.format.trim_start_matches('.').to_lowercase() != "png" {
+        return Err(anyhow::anyhow!(
+            "--stream-output requires png output format"
+        ));
+    }
 
     // Build full output path
     let output_path = build_output_path(&args.output, &args.format);
+    // A streamed capture is written before save_output could pick a free name
+    let output_path = if args.stream_output && args.auto_name {
+        next_available_path(std::path::Path::new(&output_path))
+            .to_string_lossy()
+            .into_owned()
+    } else {
+        output_path
+    };
 
-    let capture = ScreenCapture::new();
+    let stop_flag = Arc::new(Mutex::new(false));
+    install_interrupt_handler(stop_flag.clone())?;
 
-    // Resolve crop value (preset takes precedence if both are specified)
-    let crop_value = if let Some(preset_name) = &args.crop_preset {
-        let all_presets = presets::get_all_presets()?;
-        match all_presets.get(preset_name) {
-            Some(value) => {
-                println!("Using preset '{}': {}", preset_name, value);
-                Some(value.clone())
-            }
-            None => {
-                return Err(anyhow::anyhow!(
-                    "Preset '{}' not found. Use --list-presets to see available presets.",
-                    preset_name
-                ));
-            }
-        }
-    } else {
-        args.crop.clone()
+    let mut capture = ScreenCapture::new()
+        .with_stop_flag(stop_flag.clone())
+        .with_display(args.display)
+        .with_trim_first_top(args.trim_first_top)
+        .with_grayscale(args.grayscale)
+        .with_trace_cursor(args.trace_cursor)
+        .with_adaptive_threshold(args.adaptive_threshold)
+        .with_strict_crop(args.strict_crop)
+        .with_auto_overlap(args.overlap.is_auto() || args.smart_overlap)
+        .with_overlap_percent(args.overlap.percent())
+        .with_stream_output(
+            args.stream_output
+                .then(|| std::path::PathBuf::from(&output_path)),
+        )
+        .with_pixel_tolerance(args.pixel_tolerance)
+        .with_direction(args.direction)
+        .with_wheel_amount(args.wheel_amount)
+        .with_fallback_key(args.fallback_key.clone())
+        .with_compare_strip(args.compare_strip)
+        .with_metric(args.metric)
+        .with_dry_run(args.dry_run)
+        .with_seam_blend(args.seam_blend)
+        .with_stall_limit(args.stall_limit)
+        .with_dump_frames(args.dump_frames.clone())
+        .with_animation(args.animate.clone(), args.fps)
+        .with_diff_threshold(args.diff_threshold)
+        .with_trim_sticky(args.trim_sticky.then_some(args.sticky_agreement))
+        .with_min_scrolls(args.min_scrolls)
+        .with_exact_frames(args.exact_frames.map(|frames| frames as usize))
+        .with_home_first(args.home_first)
+        .with_adaptive_delay(args.adaptive_delay.then_some(args.max_settle))
+        .with_hide_cursor(args.hide_cursor)
+        .with_max_duration(args.max_duration.map(std::time::Duration::from_secs))
+        .with_window_title(args.window_title.clone());
+
+    if args.end_on_content_only {
+        capture = capture.with_end_on_content_only(ExcludedBands {
+            top: args.ignore_top,
+            bottom: args.ignore_bottom,
+            right: args.ignore_right,
+        });
+    }
+
+    let crop_value = match preset {
+        Some(preset) => Some(preset.crop),
+        None => args.crop.clone(),
     };
 
+    // Missing permissions otherwise show up as cryptic failures after the
+    // countdown; re-stitching saved frames or a video needs none of them,
+    // and a timelapse sends no input
+    if !args.skip_permission_check && args.stitch_dir.is_none() && args.from_video.is_none() {
+        let status = capture.check_permissions(!args.timelapse);
+        if !status.all_granted() {
+            return Err(anyhow::anyhow!(
+                "Missing permissions for capturing:\n{}Run again once they are granted, or pass --skip-permission-check to skip this check.",
+                status
+            ));
+        }
+    }
+
     // Handle region selection mode
     if args.select_region {
-        let (x, y, w, h) = ScreenCapture::select_region_interactive()?;
+        let (x, y, w, h) = match capture::gui::select_region_overlay(args.snap_to_window) {
+            Ok(Some(region)) => {
+                let (x, y, w, h) = region;
+                say!("Region selected: {}x{} at ({}, {})", w, h, x, y);
+                say!("Use this command:");
+                say!("   --crop \"{},{},{},{}\"", x, y, w, h);
+                say!();
+                region
+            }
+            Ok(None) => return Err(anyhow::anyhow!("Region selection cancelled")),
+            Err(e) => {
+                // No display available (e.g. over SSH), use the text prompts
+                say!("{}, falling back to text mode", e);
+                ScreenCapture::select_region_interactive(args.snap_to_window)?
+            }
+        };
 
         // Offer to run capture immediately
         use std::io::{self, Write};
@@ -214,49 +1287,170 @@ fn main() -> Result<()> {
         io::stdin().read_line(&mut input)?;
 
         if input.trim().to_lowercase() == "y" {
-            println!("\n📸 Starting capture with selected region...\n");
-            let result_image = capture.capture_with_scroll(
-                args.overlap,
-                args.max_scrolls,
-                args.delay,
-                &args.key,
-                false, // Don't use window_only
-                Some(format!("{},{},{},{}", x, y, w, h)),
-                args.scroll_delay,
-            )?;
-
-            result_image.save(&output_path)?;
-            println!("\n💾 Saved to {}", output_path);
+            say!("\n📸 Starting capture with selected region...\n");
+            let crop = Some(format!("{},{},{},{}", x, y, w, h));
+            let result_image = capture.capture_with_scroll(&capture_options(&args, false, crop))?;
+
+            let saved_path = save_output(&result_image, &output_path, &args, &capture)?;
+            say!("\n💾 Saved to {}", saved_path);
+
+            if args.trace_cursor {
+                save_cursor_trace(&capture, &result_image, &args)?;
+            }
         }
 
         return Ok(());
     }
 
+    // Re-stitch frames saved earlier, without touching the screen
+    if let Some(dir) = &args.stitch_dir {
+        say!("🧩 STITCH MODE");
+        say!("Configuration:");
+        say!("  Frames: {}", dir);
+        say!("  Output: {}", output_path);
+        say!("  Fallback overlap: {}", args.overlap.pixels());
+        say!();
+
+        let result_image =
+            capture.stitch_directory(std::path::Path::new(dir), args.overlap.pixels())?;
+
+        let saved_path = save_output(&result_image, &output_path, &args, &capture)?;
+        say!("Saved to {}", saved_path);
+
+        return Ok(());
+    }
+
+    // Stitch a screen recording made earlier
+    if let Some(video) = &args.from_video {
+        say!("🎞  VIDEO FILE MODE");
+        say!("Configuration:");
+        say!("  Video: {}", video);
+        say!("  Output: {}", output_path);
+        say!("  FPS: {}", args.fps);
+        say!("  Overlap: {}", args.overlap);
+        say!();
+
+        let result_image = capture.stitch_video(
+            std::path::Path::new(video),
+            args.overlap.pixels(),
+            args.fps,
+            crop_value.clone(),
+        )?;
+
+        let saved_path = save_output(&result_image, &output_path, &args, &capture)?;
+        say!("Saved to {}", saved_path);
+
+        return Ok(());
+    }
+
+    // Batch mode over a list of window titles
+    if let Some(targets_file) = &args.targets {
+        return run_targets(&capture, targets_file, &args, &stop_flag);
+    }
+
+    // Video mode
+    if args.video {
+        say!("🎬 VIDEO MODE");
+        say!("Configuration:");
+        say!("  Output: {}", output_path);
+        say!("  Duration: {} seconds", args.duration);
+        say!("  FPS: {}", args.fps);
+        say!("  Overlap: {}", args.overlap);
+        say!("  Scroll key: {}", args.key);
+        say!("  Direction: {:?}", args.direction);
+        say!();
+
+        let result_image = capture.capture_with_video(
+            &capture_options(&args, args.window_only, crop_value.clone()),
+            args.duration,
+            args.fps,
+        )?;
+
+        let saved_path = save_output(&result_image, &output_path, &args, &capture)?;
+        say!("Saved to {}", saved_path);
+
+        return Ok(());
+    }
+
+    // Timelapse mode
+    if args.timelapse {
+        say!("⏱  TIMELAPSE MODE");
+        say!("Configuration:");
+        say!("  Output: {}", output_path);
+        say!("  Frames: {} every {}ms", args.count, args.interval);
+        say!();
+
+        let result_image = capture.capture_timelapse(
+            args.interval,
+            args.count,
+            args.delay,
+            args.window_only,
+            crop_value.clone(),
+        )?;
+
+        let saved_path = save_output(&result_image, &output_path, &args, &capture)?;
+        say!("Saved to {}", saved_path);
+
+        return Ok(());
+    }
+
+    // Item list mode
+    if let (Some(item_height), Some(item_count)) = (args.item_height, args.item_count) {
+        say!("📋 ITEM LIST MODE");
+        say!("Configuration:");
+        say!("  Output: {}", output_path);
+        say!("  Item height: {} pixels", item_height);
+        say!("  Item count: {}", item_count);
+        say!();
+
+        let result_image = capture.capture_item_list(
+            item_height,
+            item_count,
+            args.delay,
+            args.window_only,
+            crop_value.clone(),
+            args.scroll_delay,
+        )?;
+
+        let saved_path = save_output(&result_image, &output_path, &args, &capture)?;
+        say!("Saved to {}", saved_path);
+
+        return Ok(());
+    }
+
     // Screenshot mode
-    println!("📸 SCREENSHOT MODE");
-    println!("Configuration:");
-    println!("  Output: {}", output_path);
-    println!("  Overlap: {} pixels", args.overlap);
-    if let Some(max) = args.max_scrolls {
-        println!("  Max scrolls: {}", max);
+    say!("📸 SCREENSHOT MODE");
+    say!("Configuration:");
+    say!("  Output: {}", output_path);
+    say!("  Overlap: {}", args.overlap);
+    if let Some(frames) = args.exact_frames {
+        say!("  Exact frames: {}", frames);
+    } else if let Some(max) = args.max_scrolls {
+        say!("  Max scrolls: {}", max);
     } else {
-        println!("  Max scrolls: unlimited");
+        say!("  Max scrolls: unlimited");
     }
-    println!("  Scroll key: {}", args.key);
-    println!();
+    say!("  Scroll key: {}", args.key);
+    say!("  Direction: {:?}", args.direction);
+    say!();
 
-    let result_image = capture.capture_with_scroll(
-        args.overlap,
-        args.max_scrolls,
-        args.delay,
-        &args.key,
+    let result_image = capture.capture_with_scroll(&capture_options(
+        &args,
         args.window_only,
         crop_value.clone(),
-        args.scroll_delay,
-    )?;
+    ))?;
 
-    result_image.save(&output_path)?;
-    println!("Saved to {}", output_path);
+    if args.dry_run {
+        say!("Dry run finished, {} was not written", output_path);
+        return Ok(());
+    }
+
+    let saved_path = save_output(&result_image, &output_path, &args, &capture)?;
+    say!("Saved to {}", saved_path);
+
+    if args.trace_cursor {
+        save_cursor_trace(&capture, &result_image, &args)?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,60 @@
+//! Text extraction from a stitched capture through Tesseract. Only available
+//! when built with `--features ocr`; without it [`recognize_text`] returns
+//! an error telling the user how to enable it.
+
+use anyhow::Result;
+use image::RgbaImage;
+
+/// Whether this build can run OCR
+pub fn is_available() -> bool {
+    cfg!(feature = "ocr")
+}
+
+/// Runs Tesseract over the image with the given language code(s), e.g.
+/// `eng` or `kor+eng`, and returns the recognized text. Tall captures are
+/// fed in slices so they stay within Tesseract's image size limits.
+pub fn recognize_text(img: &RgbaImage, lang: &str) -> Result<String> {
+    recognize_text_impl(img, lang)
+}
+
+#[cfg(feature = "ocr")]
+fn recognize_text_impl(img: &RgbaImage, lang: &str) -> Result<String> {
+    use crate::constants::ocr::SLICE_HEIGHT;
+
+    let mut tesseract = leptess::LepTess::new(None, lang).map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to start Tesseract for language '{}': {} (is the traineddata installed?)",
+            lang,
+            e
+        )
+    })?;
+
+    let mut text = String::new();
+    let mut top = 0;
+    while top < img.height() {
+        let height = SLICE_HEIGHT.min(img.height() - top);
+        let slice = image::imageops::crop_imm(img, 0, top, img.width(), height).to_image();
+
+        let mut encoded = std::io::Cursor::new(Vec::new());
+        slice.write_to(&mut encoded, image::ImageFormat::Png)?;
+        tesseract
+            .set_image_from_mem(encoded.get_ref())
+            .map_err(|e| anyhow::anyhow!("Failed to pass image to Tesseract: {}", e))?;
+        text.push_str(
+            &tesseract
+                .get_utf8_text()
+                .map_err(|e| anyhow::anyhow!("Tesseract returned invalid text: {}", e))?,
+        );
+
+        top += height;
+    }
+
+    Ok(text)
+}
+
+#[cfg(not(feature = "ocr"))]
+fn recognize_text_impl(_img: &RgbaImage, _lang: &str) -> Result<String> {
+    Err(anyhow::anyhow!(
+        "OCR support is not compiled in; rebuild with `cargo build --features ocr`"
+    ))
+}
@@ -1,14 +1,110 @@
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// A named capture profile: a crop region plus optional scroll settings that
+/// override the defaults when the preset is used
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Preset {
+    pub crop: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overlap: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scroll_delay: Option<u64>,
+}
+
+impl Preset {
+    /// A preset with only a crop region, as in the original string format
+    pub fn from_crop(crop: &str) -> Self {
+        Preset {
+            crop: crop.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn is_crop_only(&self) -> bool {
+        self.overlap.is_none() && self.key.is_none() && self.scroll_delay.is_none()
+    }
+}
+
+impl std::fmt::Display for Preset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.crop)?;
+        if let Some(overlap) = self.overlap {
+            write!(f, ", overlap {}px", overlap)?;
+        }
+        if let Some(key) = &self.key {
+            write!(f, ", key {}", key)?;
+        }
+        if let Some(scroll_delay) = self.scroll_delay {
+            write!(f, ", scroll delay {}ms", scroll_delay)?;
+        }
+        Ok(())
+    }
+}
+
+/// On-disk form of a preset: the plain `"x,y,w,h"` string older versions
+/// wrote, or the full object
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum StoredPreset {
+    Crop(String),
+    Full(Preset),
+}
+
+impl From<StoredPreset> for Preset {
+    fn from(stored: StoredPreset) -> Self {
+        match stored {
+            StoredPreset::Crop(crop) => Preset::from_crop(&crop),
+            StoredPreset::Full(preset) => preset,
+        }
+    }
+}
+
+impl From<&Preset> for StoredPreset {
+    fn from(preset: &Preset) -> Self {
+        // Crop-only presets stay strings so older versions can still read them
+        if preset.is_crop_only() {
+            StoredPreset::Crop(preset.crop.clone())
+        } else {
+            StoredPreset::Full(preset.clone())
+        }
+    }
+}
+
+/// Environment variable naming the presets file in place of
+/// `~/.capture-presets.json`
+pub const PRESETS_FILE_ENV: &str = "CAPTURE_PRESETS_FILE";
+
+/// Set by `--presets-file`; takes precedence over `PRESETS_FILE_ENV`
+static PRESET_FILE_OVERRIDE: std::sync::Mutex<Option<std::path::PathBuf>> =
+    std::sync::Mutex::new(None);
+
+/// Reads and writes custom presets at `path` instead of the default
+/// location, or goes back to it for `None`
+pub fn set_preset_file_path(path: Option<std::path::PathBuf>) {
+    *PRESET_FILE_OVERRIDE.lock().unwrap() = path;
+}
+
+/// The custom presets file: the `set_preset_file_path` override, then
+/// `$CAPTURE_PRESETS_FILE`, then `~/.capture-presets.json`
 pub fn get_preset_file_path() -> Result<std::path::PathBuf> {
+    if let Some(path) = PRESET_FILE_OVERRIDE.lock().unwrap().clone() {
+        return Ok(path);
+    }
+    if let Some(path) = std::env::var_os(PRESETS_FILE_ENV).filter(|path| !path.is_empty()) {
+        return Ok(path.into());
+    }
+
     let home = std::env::var("HOME")
         .or_else(|_| std::env::var("USERPROFILE"))
         .map_err(|_| anyhow::anyhow!("Could not find home directory"))?;
     Ok(std::path::PathBuf::from(home).join(".capture-presets.json"))
 }
 
-pub fn load_presets() -> Result<HashMap<String, String>> {
+pub fn load_presets() -> Result<HashMap<String, Preset>> {
     let preset_file = get_preset_file_path()?;
 
     if !preset_file.exists() {
@@ -16,37 +112,59 @@ pub fn load_presets() -> Result<HashMap<String, String>> {
     }
 
     let content = std::fs::read_to_string(&preset_file)?;
-    let presets: HashMap<String, String> =
+    let presets: HashMap<String, StoredPreset> =
         serde_json::from_str(&content).unwrap_or_else(|_| HashMap::new());
 
-    Ok(presets)
+    Ok(presets
+        .into_iter()
+        .map(|(name, stored)| (name, stored.into()))
+        .collect())
 }
 
-pub fn save_presets(presets: &HashMap<String, String>) -> Result<()> {
+pub fn save_presets(presets: &HashMap<String, Preset>) -> Result<()> {
     let preset_file = get_preset_file_path()?;
-    let content = serde_json::to_string_pretty(presets)?;
+    let stored: HashMap<&String, StoredPreset> = presets
+        .iter()
+        .map(|(name, preset)| (name, preset.into()))
+        .collect();
+    let content = serde_json::to_string_pretty(&stored)?;
     std::fs::write(&preset_file, content)?;
     Ok(())
 }
 
-pub fn get_builtin_presets() -> HashMap<String, String> {
+pub fn get_builtin_presets() -> HashMap<String, Preset> {
     let mut presets = HashMap::new();
 
     // Common screen resolutions
-    presets.insert("1080p".to_string(), "0,0,1920,1080".to_string());
-    presets.insert("720p".to_string(), "0,0,1280,720".to_string());
-    presets.insert("4k".to_string(), "0,0,3840,2160".to_string());
-    presets.insert("naver-series".to_string(), "607,23,690,1007".to_string());
+    presets.insert("1080p".to_string(), Preset::from_crop("0,0,1920,1080"));
+    presets.insert("720p".to_string(), Preset::from_crop("0,0,1280,720"));
+    presets.insert("4k".to_string(), Preset::from_crop("0,0,3840,2160"));
+    presets.insert(
+        "naver-series".to_string(),
+        Preset::from_crop("607,23,690,1007"),
+    );
+
+    // Whole display, whatever its resolution
+    presets.insert("full".to_string(), Preset::from_crop("0,0,full,full"));
 
     // VM window presets (common sizes)
-    presets.insert("vm-small".to_string(), "100,100,1024,768".to_string());
-    presets.insert("vm-medium".to_string(), "100,100,1280,800".to_string());
-    presets.insert("vm-large".to_string(), "100,100,1920,1080".to_string());
+    presets.insert(
+        "vm-small".to_string(),
+        Preset::from_crop("100,100,1024,768"),
+    );
+    presets.insert(
+        "vm-medium".to_string(),
+        Preset::from_crop("100,100,1280,800"),
+    );
+    presets.insert(
+        "vm-large".to_string(),
+        Preset::from_crop("100,100,1920,1080"),
+    );
 
     presets
 }
 
-pub fn get_all_presets() -> Result<HashMap<String, String>> {
+pub fn get_all_presets() -> Result<HashMap<String, Preset>> {
     let mut all_presets = get_builtin_presets();
     let custom_presets = load_presets()?;
 
@@ -56,13 +174,127 @@ pub fn get_all_presets() -> Result<HashMap<String, String>> {
     Ok(all_presets)
 }
 
+/// Width/height token meaning "the rest of the screen from the offset",
+/// resolved against the live display size at capture time
+pub const FULL_TOKEN: &str = "full";
+
+fn crop_parts(crop_str: &str) -> Vec<&str> {
+    crop_str
+        .split([',', ':', ' '])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// A crop region in screen pixels, or in percent of the screen so the same
+/// crop (e.g. `10%,0%,80%,100%`) fits displays of any resolution
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CropSpec {
+    Pixels(i32, i32, i32, i32),
+    Percent(f32, f32, f32, f32),
+}
+
+impl CropSpec {
+    /// Parses "x,y,width,height" where either none or all of the values end
+    /// in `%`. Percentages must lie within 0-100 with a non-zero size.
+    pub fn parse(crop_str: &str) -> Option<Self> {
+        let parts = crop_parts(crop_str);
+        if parts.len() != 4 {
+            return None;
+        }
+
+        let percents: Vec<&str> = parts.iter().filter_map(|p| p.strip_suffix('%')).collect();
+        if percents.is_empty() {
+            let (x, y, w, h) = parse_crop_region(crop_str)?;
+            return Some(CropSpec::Pixels(x, y, w, h));
+        }
+        if percents.len() != 4 {
+            return None;
+        }
+
+        let values: Vec<f32> = percents
+            .iter()
+            .map(|p| p.trim().parse().ok())
+            .collect::<Option<_>>()?;
+        let in_range = values.iter().all(|v| (0.0..=100.0).contains(v));
+        (in_range && values[2] > 0.0 && values[3] > 0.0)
+            .then(|| CropSpec::Percent(values[0], values[1], values[2], values[3]))
+    }
+
+    /// The region in pixels on a screen of the given size
+    pub fn resolve(&self, screen_width: i32, screen_height: i32) -> (i32, i32, i32, i32) {
+        match *self {
+            CropSpec::Pixels(x, y, w, h) => (x, y, w, h),
+            CropSpec::Percent(x, y, w, h) => {
+                let of = |percent: f32, total: i32| {
+                    (percent as f64 / 100.0 * total as f64).round() as i32
+                };
+                (
+                    of(x, screen_width),
+                    of(y, screen_height),
+                    of(w, screen_width).max(1),
+                    of(h, screen_height).max(1),
+                )
+            }
+        }
+    }
+}
+
+/// Whether the crop region needs the screen size to be resolved
+pub fn uses_screen_size(crop_str: &str) -> bool {
+    crop_parts(crop_str)
+        .iter()
+        .any(|part| part.eq_ignore_ascii_case(FULL_TOKEN) || part.ends_with('%'))
+}
+
+/// Like [`parse_crop_region`], but also accepts `full` as the width or height,
+/// expanding it to the screen extent remaining after the x/y offset, and
+/// percentages of the screen (see [`CropSpec`])
+pub fn parse_crop_region_with_screen(
+    crop_str: &str,
+    screen_width: i32,
+    screen_height: i32,
+) -> Option<(i32, i32, i32, i32)> {
+    if let Some(spec) = CropSpec::parse(crop_str) {
+        return Some(spec.resolve(screen_width, screen_height));
+    }
+
+    let parts = crop_parts(crop_str);
+    if parts.len() != 4 {
+        return None;
+    }
+
+    let x: i32 = parts[0].parse().ok()?;
+    let y: i32 = parts[1].parse().ok()?;
+    let extent = |part: &str, available: i32| {
+        if part.eq_ignore_ascii_case(FULL_TOKEN) {
+            Some(available)
+        } else {
+            part.parse().ok()
+        }
+    };
+    let w = extent(parts[2], screen_width.saturating_sub(x))?;
+    let h = extent(parts[3], screen_height.saturating_sub(y))?;
+
+    (w >= 0 && h >= 0).then_some((x, y, w, h))
+}
+
+/// Checks the format of a crop region that may contain `full` tokens, and
+/// that its size isn't zero
+pub fn is_valid_crop_region(crop_str: &str) -> bool {
+    // Any real screen is smaller than this, so `full` always resolves positive
+    parse_crop_region_with_screen(crop_str, i32::MAX, i32::MAX)
+        .is_some_and(|(_, _, w, h)| w > 0 && h > 0)
+}
+
 pub fn parse_crop_region(crop_str: &str) -> Option<(i32, i32, i32, i32)> {
     let parts: Vec<i32> = crop_str
-        .split(|c| c == ',' || c == ':' || c == ' ')
+        .split([',', ':', ' '])
         .filter_map(|s| s.trim().parse().ok())
         .collect();
 
-    if parts.len() == 4 && parts[2] > 0 && parts[3] > 0 {
+    // A zero size parses, and is rejected as an empty frame on capture
+    if parts.len() == 4 && parts[2] >= 0 && parts[3] >= 0 {
         Some((parts[0], parts[1], parts[2], parts[3]))
     } else {
         None
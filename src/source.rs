@@ -0,0 +1,90 @@
+//! Where the scroll loop gets its frames from: the live screen, or a
+//! scripted sequence so the loop can run without a display (tests,
+//! benchmarks, re-stitching recorded frames).
+
+use crate::ScreenCapture;
+use anyhow::Result;
+use image::RgbaImage;
+
+/// A supplier of frames for [`ScreenCapture::capture_from_source`]
+pub trait CaptureSource {
+    /// Grabs the next frame
+    fn capture_frame(&self) -> Result<RgbaImage>;
+
+    /// Whether frames come from the live screen, so the loop has to send
+    /// scroll input and wait for the content to settle. Scripted sources
+    /// return false and skip both.
+    fn is_live(&self) -> bool {
+        true
+    }
+}
+
+impl<T: CaptureSource + ?Sized> CaptureSource for &T {
+    fn capture_frame(&self) -> Result<RgbaImage> {
+        (**self).capture_frame()
+    }
+
+    fn is_live(&self) -> bool {
+        (**self).is_live()
+    }
+}
+
+/// The real backend: the capture's display, cropped to `crop_region`
+pub(crate) struct ScreenSource<'a> {
+    capture: &'a ScreenCapture,
+    crop_region: Option<(i32, i32, i32, i32)>,
+}
+
+impl<'a> ScreenSource<'a> {
+    pub(crate) fn new(
+        capture: &'a ScreenCapture,
+        crop_region: Option<(i32, i32, i32, i32)>,
+    ) -> Self {
+        Self {
+            capture,
+            crop_region,
+        }
+    }
+}
+
+impl CaptureSource for ScreenSource<'_> {
+    fn capture_frame(&self) -> Result<RgbaImage> {
+        self.capture.capture_screen(self.crop_region)
+    }
+}
+
+/// Replays a fixed list of frames in order. Once it runs out it keeps
+/// returning the last frame, like a page that has stopped scrolling, so the
+/// loop ends through its usual end-of-content detection.
+pub struct MockSource {
+    frames: Vec<RgbaImage>,
+    next: std::sync::atomic::AtomicUsize,
+}
+
+impl MockSource {
+    pub fn new(frames: Vec<RgbaImage>) -> Self {
+        Self {
+            frames,
+            next: std::sync::atomic::AtomicUsize::new(0),
+        }
+    }
+
+    /// Frames handed out so far, counting repeats of the last one
+    pub fn frames_served(&self) -> usize {
+        self.next.load(std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+impl CaptureSource for MockSource {
+    fn capture_frame(&self) -> Result<RgbaImage> {
+        let index = self.next.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        self.frames
+            .get(index.min(self.frames.len().saturating_sub(1)))
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("MockSource has no frames"))
+    }
+
+    fn is_live(&self) -> bool {
+        false
+    }
+}
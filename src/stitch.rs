@@ -0,0 +1,650 @@
+//! Frame comparison and stitching on plain `RgbaImage`s, independent of
+//! how the frames were captured.
+//!
+//! ```
+//! use capture::ScrollDirection;
+//! use capture::stitch::stitch_images;
+//! use image::{Rgba, RgbaImage};
+//!
+//! let red = RgbaImage::from_pixel(4, 4, Rgba([255, 0, 0, 255]));
+//! let blue = RgbaImage::from_pixel(4, 4, Rgba([0, 0, 255, 255]));
+//!
+//! // 2px overlap: 4 + 4 - 2 rows, cut at the middle of the overlap
+//! let stitched = stitch_images(&[red, blue], &[2], ScrollDirection::Vertical, false);
+//! assert_eq!(stitched.dimensions(), (4, 6));
+//! assert_eq!(*stitched.get_pixel(0, 2), Rgba([255, 0, 0, 255]));
+//! assert_eq!(*stitched.get_pixel(0, 3), Rgba([0, 0, 255, 255]));
+//! ```
+
+use crate::ScrollDirection;
+use crate::constants::{overlap_detection, similarity};
+use image::{ImageBuffer, Pixel, Rgba, RgbaImage};
+use rayon::prelude::*;
+
+/// Whether both images have the same size and exactly the same pixels
+pub fn images_are_identical(img1: &RgbaImage, img2: &RgbaImage) -> bool {
+    if img1.dimensions() != img2.dimensions() {
+        return false;
+    }
+
+    // Compare the raw RGBA buffers in parallel; `any` stops all workers
+    // as soon as one chunk differs
+    !img1
+        .as_raw()
+        .par_chunks(similarity::IDENTICAL_CHUNK_BYTES)
+        .zip(img2.as_raw().par_chunks(similarity::IDENTICAL_CHUNK_BYTES))
+        .any(|(chunk1, chunk2)| chunk1 != chunk2)
+}
+
+/// Whether every pixel is black (R/G/B at most `BLANK_TOLERANCE`) or fully
+/// transparent, as some platforms return for a frame grabbed mid app switch
+pub fn is_blank(img: &RgbaImage) -> bool {
+    img.pixels()
+        .all(|p| p[3] == 0 || p.0[..3].iter().all(|&c| c <= similarity::BLANK_TOLERANCE))
+}
+
+/// Rows compared by [`images_are_similar`]: `overlap_height`, or the whole
+/// image when it is 0 or taller than the image
+pub fn band_height(image_height: u32, overlap_height: u32) -> u32 {
+    if overlap_height == 0 || overlap_height > image_height {
+        image_height
+    } else {
+        overlap_height
+    }
+}
+
+/// Compares the bottom `overlap_height` rows of both images and returns
+/// whether at most `threshold` percent of them differ, along with that
+/// percentage. Pixels whose R/G/B channels all differ by at most `tolerance`
+/// count as equal.
+pub fn images_are_similar(
+    img1: &RgbaImage,
+    img2: &RgbaImage,
+    overlap_height: u32,
+    tolerance: u8,
+    threshold: f32,
+) -> (bool, f32) {
+    images_are_similar_in_columns(
+        img1,
+        img2,
+        overlap_height,
+        tolerance,
+        threshold,
+        0..img1.width(),
+    )
+}
+
+/// [`images_are_similar`] restricted to the given column range, e.g. a
+/// [`centered_columns`] strip that skips static chrome on wide captures
+pub fn images_are_similar_in_columns(
+    img1: &RgbaImage,
+    img2: &RgbaImage,
+    overlap_height: u32,
+    tolerance: u8,
+    threshold: f32,
+    columns: std::ops::Range<u32>,
+) -> (bool, f32) {
+    if img1.dimensions() != img2.dimensions() {
+        return (false, 100.0);
+    }
+
+    let (width, height) = img1.dimensions();
+    let columns = columns.start.min(width)..columns.end.min(width);
+    let band_height = band_height(height, overlap_height);
+    let band_top = height - band_height;
+
+    let mut diff_count = 0;
+    for y in band_top..height {
+        for x in columns.clone() {
+            let p1 = img1.get_pixel(x, y);
+            let p2 = img2.get_pixel(x, y);
+            let max_delta = (0..3).map(|c| p1[c].abs_diff(p2[c])).max().unwrap_or(0);
+            if max_delta > tolerance {
+                diff_count += 1;
+            }
+        }
+    }
+
+    let total_pixels = (columns.len() as u32 * band_height) as f32;
+    let diff_percentage = if total_pixels > 0.0 {
+        diff_count as f32 / total_pixels * 100.0
+    } else {
+        0.0
+    };
+
+    (diff_percentage <= threshold, diff_percentage)
+}
+
+/// Mean structural similarity (SSIM) of the two images' luma, computed over
+/// non-overlapping `SSIM_WINDOW` squares: 1.0 for identical images, lower as
+/// structure diverges. Unlike a pixel count it shrugs off antialiasing and
+/// slight color shifts. Returns 0.0 when the sizes differ.
+pub fn ssim(img1: &RgbaImage, img2: &RgbaImage) -> f32 {
+    if img1.dimensions() != img2.dimensions() {
+        return 0.0;
+    }
+
+    let luma = |p: &Rgba<u8>| p[0] as f64 * 0.299 + p[1] as f64 * 0.587 + p[2] as f64 * 0.114;
+    let (width, height) = img1.dimensions();
+    let window = similarity::SSIM_WINDOW;
+
+    let mut total = 0.0;
+    let mut windows = 0;
+    for top in (0..height).step_by(window as usize) {
+        for left in (0..width).step_by(window as usize) {
+            let (mut sum1, mut sum2, mut sq1, mut sq2, mut cross) = (0.0, 0.0, 0.0, 0.0, 0.0);
+            let mut n = 0.0;
+            for y in top..(top + window).min(height) {
+                for x in left..(left + window).min(width) {
+                    let a = luma(img1.get_pixel(x, y));
+                    let b = luma(img2.get_pixel(x, y));
+                    sum1 += a;
+                    sum2 += b;
+                    sq1 += a * a;
+                    sq2 += b * b;
+                    cross += a * b;
+                    n += 1.0;
+                }
+            }
+
+            let (mean1, mean2) = (sum1 / n, sum2 / n);
+            let var1 = sq1 / n - mean1 * mean1;
+            let var2 = sq2 / n - mean2 * mean2;
+            let covariance = cross / n - mean1 * mean2;
+            total += ((2.0 * mean1 * mean2 + similarity::SSIM_C1)
+                * (2.0 * covariance + similarity::SSIM_C2))
+                / ((mean1 * mean1 + mean2 * mean2 + similarity::SSIM_C1)
+                    * (var1 + var2 + similarity::SSIM_C2));
+            windows += 1;
+        }
+    }
+
+    if windows == 0 {
+        return 1.0;
+    }
+    (total / windows as f64) as f32
+}
+
+/// [`ssim`] of the bottom `overlap_height` rows (see [`band_height`]) of
+/// both images, restricted to the given column range
+pub fn band_ssim(
+    img1: &RgbaImage,
+    img2: &RgbaImage,
+    overlap_height: u32,
+    columns: std::ops::Range<u32>,
+) -> f32 {
+    if img1.dimensions() != img2.dimensions() {
+        return 0.0;
+    }
+
+    let (width, height) = img1.dimensions();
+    let columns = columns.start.min(width)..columns.end.min(width);
+    let band_height = band_height(height, overlap_height);
+    let band_top = height - band_height;
+    let crop = |img: &RgbaImage| {
+        image::imageops::crop_imm(
+            img,
+            columns.start,
+            band_top,
+            columns.len() as u32,
+            band_height,
+        )
+        .to_image()
+    };
+    ssim(&crop(img1), &crop(img2))
+}
+
+/// [`ssim`] between `img1`'s bottom and `img2`'s top `candidate` rows, the
+/// SSIM counterpart of [`overlap_mean_diff`]
+pub fn overlap_ssim(img1: &RgbaImage, img2: &RgbaImage, candidate: u32) -> f32 {
+    if img1.width() != img2.width() || candidate > img1.height().min(img2.height()) {
+        return 0.0;
+    }
+    let bottom =
+        image::imageops::crop_imm(img1, 0, img1.height() - candidate, img1.width(), candidate)
+            .to_image();
+    let top = image::imageops::crop_imm(img2, 0, 0, img2.width(), candidate).to_image();
+    ssim(&bottom, &top)
+}
+
+/// The middle `fraction` of `width` columns, at least one column wide
+pub fn centered_columns(width: u32, fraction: f32) -> std::ops::Range<u32> {
+    let strip = ((width as f32 * fraction.clamp(0.0, 1.0)).round() as u32).clamp(1, width.max(1));
+    let start = (width.saturating_sub(strip)) / 2;
+    start..(start + strip).min(width)
+}
+
+/// Mean per-channel difference (sum of absolute differences over the
+/// sampled pixels) between `img1`'s bottom and `img2`'s top `candidate` rows
+pub fn overlap_mean_diff(img1: &RgbaImage, img2: &RgbaImage, candidate: u32) -> f32 {
+    let height1 = img1.height();
+    let mut diff_sum: u64 = 0;
+    let mut samples: u64 = 0;
+
+    for y in 0..candidate {
+        for x in (0..img1.width()).step_by(overlap_detection::SAMPLE_STRIDE) {
+            let p1 = img1.get_pixel(x, height1 - candidate + y);
+            let p2 = img2.get_pixel(x, y);
+            diff_sum += (0..3)
+                .map(|c| (p1[c] as i32 - p2[c] as i32).unsigned_abs() as u64)
+                .sum::<u64>();
+            samples += 1;
+        }
+    }
+
+    diff_sum as f32 / samples.max(1) as f32
+}
+
+/// Slides `img2`'s top over `img1`'s bottom for overlaps up to `max_overlap`
+/// rows and returns the one with the lowest mean difference, with that
+/// difference. `None` when the widths differ or no candidate fits.
+pub fn best_overlap(img1: &RgbaImage, img2: &RgbaImage, max_overlap: u32) -> Option<(u32, f32)> {
+    if img1.width() != img2.width() {
+        return None;
+    }
+
+    let max_overlap = max_overlap.min(img1.height().min(img2.height()).saturating_sub(1));
+
+    let mut best: Option<(u32, f32)> = None;
+    for candidate in overlap_detection::MIN_OVERLAP..=max_overlap {
+        let mean_diff = overlap_mean_diff(img1, img2, candidate);
+        if best.is_none_or(|(_, best_diff)| mean_diff < best_diff) {
+            best = Some((candidate, mean_diff));
+        }
+    }
+    best
+}
+
+/// How many rows of `img2`'s top repeat `img1`'s bottom, searching up to
+/// `max_overlap` rows. Returns `None` when no candidate matches closely enough.
+pub fn detect_overlap(img1: &RgbaImage, img2: &RgbaImage, max_overlap: u32) -> Option<u32> {
+    best_overlap(img1, img2, max_overlap)
+        .filter(|&(_, mean_diff)| mean_diff <= overlap_detection::MAX_MEAN_DIFF)
+        .map(|(overlap, _)| overlap)
+}
+
+/// Whether `img2` lines up above `img1` rather than below it, i.e. the
+/// content moved the wrong way: `img1`'s top repeating `img2`'s bottom
+/// matches better than the forward alignment.
+pub fn scrolled_backwards(img1: &RgbaImage, img2: &RgbaImage, max_overlap: u32) -> bool {
+    let Some((_, backward_diff)) = best_overlap(img2, img1, max_overlap) else {
+        return false;
+    };
+    if backward_diff > overlap_detection::MAX_MEAN_DIFF {
+        return false;
+    }
+    best_overlap(img1, img2, max_overlap)
+        .is_none_or(|(_, forward_diff)| backward_diff < forward_diff)
+}
+
+/// Caps each overlap one row short of the shorter frame of its pair, so
+/// every frame adds at least one row (e.g. the default 125px overlap with a
+/// 100px crop). Missing entries count as 0.
+pub fn clamp_overlaps(
+    images: &[RgbaImage],
+    overlaps: &[u32],
+    direction: ScrollDirection,
+) -> Vec<u32> {
+    images
+        .windows(2)
+        .enumerate()
+        .map(|(i, pair)| {
+            let max_overlap = direction
+                .length(&pair[0])
+                .min(direction.length(&pair[1]))
+                .saturating_sub(1);
+            overlaps.get(i).copied().unwrap_or(0).min(max_overlap)
+        })
+        .collect()
+}
+
+/// Returns the offset of each frame along the scroll axis within the
+/// stitched image, where `overlaps[i]` is the overlap between frame `i`
+/// and frame `i + 1` (capped as in [`clamp_overlaps`]).
+pub fn frame_offsets(
+    images: &[RgbaImage],
+    overlaps: &[u32],
+    direction: ScrollDirection,
+) -> Vec<u32> {
+    let overlaps = clamp_overlaps(images, overlaps, direction);
+    let mut offsets = Vec::with_capacity(images.len());
+    let mut offset = 0;
+
+    for (i, img) in images.iter().enumerate() {
+        offsets.push(offset);
+        // Frames may differ in length (e.g. a trimmed first frame)
+        let overlap = overlaps.get(i).copied().unwrap_or(0);
+        offset += direction.length(img).saturating_sub(overlap);
+    }
+
+    offsets
+}
+
+/// Concatenates frames along the scroll axis: top to bottom when vertical,
+/// left to right when horizontal. `overlaps[i]` is the overlap between frame
+/// `i` and frame `i + 1`, capped as in [`clamp_overlaps`]; with `seam_blend`
+/// the frames cross-fade across the whole overlap instead of cutting at its
+/// middle.
+pub fn stitch_images(
+    images: &[RgbaImage],
+    overlaps: &[u32],
+    direction: ScrollDirection,
+    seam_blend: bool,
+) -> RgbaImage {
+    if images.is_empty() {
+        return ImageBuffer::new(1, 1);
+    }
+
+    let breadth = direction.breadth(&images[0]);
+    let overlaps = clamp_overlaps(images, overlaps, direction);
+    let offsets = frame_offsets(images, &overlaps, direction);
+    let total_length = offsets[images.len() - 1] + direction.length(&images[images.len() - 1]);
+
+    let (width, height) = match direction {
+        ScrollDirection::Vertical => (breadth, total_length),
+        ScrollDirection::Horizontal => (total_length, breadth),
+    };
+    let row_bytes = width as usize * 4;
+    let mut raw = vec![0u8; row_bytes * height as usize];
+
+    // Output rows are independent: each one replays, in frame order, the
+    // spans the frames write to it. Vertically a frame writes whole rows,
+    // horizontally a run of each row, so both copy slices.
+    raw.par_chunks_mut(row_bytes.max(1))
+        .enumerate()
+        .for_each(|(y, row)| {
+            let y = y as u32;
+            for (i, img) in images.iter().enumerate() {
+                let offset = offsets[i];
+                let overlap = if i > 0 { overlaps[i - 1] } else { 0 };
+                let blend = i > 0 && seam_blend;
+                // Hard cut: use middle of overlap as boundary, so the first
+                // half stays from the previous image and the second half
+                // comes from the current one
+                let first = if i > 0 && !seam_blend { overlap / 2 } else { 0 };
+
+                match direction {
+                    ScrollDirection::Vertical => {
+                        // Offsets only grow, so no later frame reaches this row
+                        let Some(along) = y.checked_sub(offset) else {
+                            break;
+                        };
+                        if along < first || along >= img.height() {
+                            continue;
+                        }
+                        let across = breadth.min(img.width()) as usize * 4;
+                        let start = along as usize * img.width() as usize * 4;
+                        let source = &img.as_raw()[start..start + across];
+                        if blend && along < overlap {
+                            blend_span(&mut row[..across], source, |_| along + 1, overlap + 1);
+                        } else {
+                            row[..across].copy_from_slice(source);
+                        }
+                    }
+                    ScrollDirection::Horizontal => {
+                        if y >= img.height() {
+                            continue;
+                        }
+                        let end = img.width().min(total_length - offset);
+                        if first >= end {
+                            continue;
+                        }
+                        let start = y as usize * img.width() as usize * 4;
+                        let source =
+                            &img.as_raw()[start + first as usize * 4..start + end as usize * 4];
+                        let target =
+                            &mut row[(offset + first) as usize * 4..(offset + end) as usize * 4];
+
+                        let blended = if blend { overlap.min(end) - first } else { 0 } as usize * 4;
+                        blend_span(
+                            &mut target[..blended],
+                            &source[..blended],
+                            |k| first + k + 1,
+                            overlap + 1,
+                        );
+                        target[blended..].copy_from_slice(&source[blended..]);
+                    }
+                }
+            }
+        });
+
+    RgbaImage::from_raw(width, height, raw).expect("buffer sized to the stitched image")
+}
+
+/// Blends each pixel of `source` over `target` in place, the `k`th pixel at
+/// `step(k) / steps` as in [`blend_pixels`]
+fn blend_span(target: &mut [u8], source: &[u8], step: impl Fn(u32) -> u32, steps: u32) {
+    for (k, (to, from)) in target
+        .chunks_exact_mut(4)
+        .zip(source.chunks_exact(4))
+        .enumerate()
+    {
+        let blended = blend_pixels(
+            Rgba::from_slice(to),
+            Rgba::from_slice(from),
+            step(k as u32),
+            steps,
+        );
+        to.copy_from_slice(&blended.0);
+    }
+}
+
+/// Linear interpolation from `from` to `to` by `step / steps`, in integer
+/// math on each u8 channel
+fn blend_pixels(from: &Rgba<u8>, to: &Rgba<u8>, step: u32, steps: u32) -> Rgba<u8> {
+    let step = step.min(steps);
+    let mut blended = [0u8; 4];
+    for (c, channel) in blended.iter_mut().enumerate() {
+        let mixed = (from[c] as u32 * (steps - step) + to[c] as u32 * step + steps / 2) / steps;
+        *channel = mixed.min(255) as u8;
+    }
+    Rgba(blended)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The original pixel-by-pixel stitch, kept as the reference for the
+    /// row-copying version
+    fn reference_stitch(
+        images: &[RgbaImage],
+        overlaps: &[u32],
+        direction: ScrollDirection,
+        seam_blend: bool,
+    ) -> RgbaImage {
+        let breadth = direction.breadth(&images[0]);
+        let overlaps = clamp_overlaps(images, overlaps, direction);
+        let offsets = frame_offsets(images, &overlaps, direction);
+        let total_length = offsets[images.len() - 1] + direction.length(&images[images.len() - 1]);
+
+        let mut result = match direction {
+            ScrollDirection::Vertical => ImageBuffer::new(breadth, total_length),
+            ScrollDirection::Horizontal => ImageBuffer::new(total_length, breadth),
+        };
+
+        for (i, img) in images.iter().enumerate() {
+            let offset = offsets[i];
+            let overlap = if i > 0 { overlaps[i - 1] } else { 0 };
+
+            for along in 0..direction.length(img) {
+                let in_overlap = i > 0 && along < overlap;
+
+                // Hard cut: use middle of overlap as boundary, so the first half
+                // stays from the previous image and the second half comes from
+                // the current one
+                if in_overlap && !seam_blend && along < overlap / 2 {
+                    continue;
+                }
+
+                let target = offset + along;
+                if target >= total_length {
+                    continue;
+                }
+
+                for across in 0..breadth.min(direction.breadth(img)) {
+                    let (source, destination) = match direction {
+                        ScrollDirection::Vertical => ((across, along), (across, target)),
+                        ScrollDirection::Horizontal => ((along, across), (target, across)),
+                    };
+
+                    let mut pixel = *img.get_pixel(source.0, source.1);
+                    if in_overlap && seam_blend {
+                        let previous = result.get_pixel(destination.0, destination.1);
+                        pixel = blend_pixels(previous, &pixel, along + 1, overlap + 1);
+                    }
+                    result.put_pixel(destination.0, destination.1, pixel);
+                }
+            }
+        }
+
+        result
+    }
+
+    #[test]
+    fn row_copying_matches_the_per_pixel_stitch() {
+        let frame = |width, height, seed: u32| {
+            RgbaImage::from_fn(width, height, |x, y| {
+                Rgba([
+                    (x * 7 + seed) as u8,
+                    (y * 3 + seed) as u8,
+                    (x ^ y) as u8,
+                    255,
+                ])
+            })
+        };
+        // Mixed sizes, a narrower frame and overlaps past the frame length
+        let frames = [
+            frame(12, 20, 0),
+            frame(12, 9, 40),
+            frame(10, 30, 80),
+            frame(12, 6, 120),
+        ];
+        let overlaps = [7, 25, 3];
+
+        for direction in [ScrollDirection::Vertical, ScrollDirection::Horizontal] {
+            for seam_blend in [false, true] {
+                assert_eq!(
+                    stitch_images(&frames, &overlaps, direction, seam_blend),
+                    reference_stitch(&frames, &overlaps, direction, seam_blend),
+                    "{:?}, seam_blend {}",
+                    direction,
+                    seam_blend
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn overlap_taller_than_frame_is_clamped() {
+        let top = RgbaImage::from_pixel(4, 100, Rgba([255, 0, 0, 255]));
+        let bottom = RgbaImage::from_pixel(4, 100, Rgba([0, 0, 255, 255]));
+        let frames = [top, bottom];
+
+        assert_eq!(
+            clamp_overlaps(&frames, &[125], ScrollDirection::Vertical),
+            vec![99]
+        );
+
+        // Each frame still contributes at least one row
+        let stitched = stitch_images(&frames, &[125], ScrollDirection::Vertical, false);
+        assert_eq!(stitched.dimensions(), (4, 101));
+        assert_eq!(*stitched.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+        assert_eq!(*stitched.get_pixel(0, 100), Rgba([0, 0, 255, 255]));
+
+        let blended = stitch_images(&frames, &[125], ScrollDirection::Vertical, true);
+        assert_eq!(blended.dimensions(), (4, 101));
+    }
+
+    #[test]
+    fn frames_of_different_heights_fill_the_result_exactly() {
+        let page = RgbaImage::from_fn(6, 300, |x, y| Rgba([y as u8, (y / 2) as u8, x as u8, 255]));
+        let view = |top, height| image::imageops::crop_imm(&page, 0, top, 6, height).to_image();
+
+        // A trimmed first frame, two full frames and a clipped last frame,
+        // each overlapping the one before by 20 rows
+        let frames = [view(0, 60), view(40, 100), view(120, 100), view(200, 45)];
+        let overlaps = [20, 20, 20];
+
+        assert_eq!(
+            frame_offsets(&frames, &overlaps, ScrollDirection::Vertical),
+            vec![0, 40, 120, 200]
+        );
+        let stitched = stitch_images(&frames, &overlaps, ScrollDirection::Vertical, false);
+        assert_eq!(stitched, view(0, 245));
+        let blended = stitch_images(&frames, &overlaps, ScrollDirection::Vertical, true);
+        assert_eq!(blended, view(0, 245));
+    }
+
+    #[test]
+    fn short_last_frame_keeps_its_new_rows() {
+        let page = RgbaImage::from_fn(4, 200, |_, y| Rgba([y as u8, 0, 0, 255]));
+        let view = |top, height| image::imageops::crop_imm(&page, 0, top, 4, height).to_image();
+
+        // The last frame is shorter than the overlap used elsewhere, so the
+        // overlap is clamped and its final row still lands in the result
+        let frames = [view(0, 100), view(70, 100), view(160, 25)];
+        let stitched = stitch_images(&frames, &[30, 30], ScrollDirection::Vertical, false);
+
+        // Offsets 0, 70 and 70 + (100 - 24)
+        assert_eq!(stitched.height(), 146 + 25);
+        assert_eq!(*stitched.get_pixel(0, 170), Rgba([184, 0, 0, 255]));
+    }
+
+    #[test]
+    fn compare_strip_ignores_changes_outside_it() {
+        let img1 = RgbaImage::from_pixel(100, 10, Rgba([0, 0, 0, 255]));
+        let mut img2 = img1.clone();
+        // A clock in the corner changes, the content in the middle doesn't
+        for y in 0..10 {
+            for x in 90..100 {
+                img2.put_pixel(x, y, Rgba([255, 255, 255, 255]));
+            }
+        }
+
+        assert_eq!(centered_columns(100, 0.2), 40..60);
+        assert_eq!(images_are_similar(&img1, &img2, 10, 0, 0.5), (false, 10.0));
+        assert_eq!(
+            images_are_similar_in_columns(&img1, &img2, 10, 0, 0.5, centered_columns(100, 0.2)),
+            (true, 0.0)
+        );
+    }
+
+    #[test]
+    fn upward_scroll_is_detected() {
+        let page = RgbaImage::from_fn(8, 300, |x, y| Rgba([y as u8, (y / 2) as u8, x as u8, 255]));
+        let view = |top| image::imageops::crop_imm(&page, 0, top, 8, 100).to_image();
+
+        assert!(scrolled_backwards(&view(100), &view(60), 99));
+        assert!(!scrolled_backwards(&view(100), &view(140), 99));
+    }
+
+    #[test]
+    fn ssim_tolerates_noise_but_not_new_content() {
+        let page = RgbaImage::from_fn(32, 32, |x, y| {
+            let v = if (x / 4 + y / 4) % 2 == 0 { 40 } else { 220 };
+            Rgba([v, v, v, 255])
+        });
+        let mut noisy = page.clone();
+        for (x, y, pixel) in noisy.enumerate_pixels_mut() {
+            if (x + y) % 3 == 0 {
+                pixel.0[0] = pixel.0[0].saturating_add(4);
+            }
+        }
+        let other = RgbaImage::from_fn(32, 32, |x, _| {
+            let v = if x % 2 == 0 { 40 } else { 220 };
+            Rgba([v, v, v, 255])
+        });
+
+        assert_eq!(ssim(&page, &page), 1.0);
+        assert!(ssim(&page, &noisy) > similarity::SSIM_THRESHOLD);
+        assert!(ssim(&page, &other) < 0.5);
+        assert_eq!(
+            band_ssim(&page, &noisy, 8, 0..32),
+            ssim(
+                &image::imageops::crop_imm(&page, 0, 24, 32, 8).to_image(),
+                &image::imageops::crop_imm(&noisy, 0, 24, 32, 8).to_image(),
+            )
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! Incremental stitching for very long captures (`--stream-output`). Rows
+//! are written out as soon as no later frame can replace them, so memory
+//! stays bounded to a couple of frames however long the page gets.
+//!
+//! PNG needs the final height in its header, so rows are spooled to a raw
+//! file next to the output and encoded row by row once the capture ends.
+
+use anyhow::Result;
+use image::RgbaImage;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Stitches vertical frames top to bottom with the hard cut of
+/// [`crate::stitch::stitch_images`], writing finished rows to disk
+pub struct StreamingStitcher {
+    path: PathBuf,
+    spool_path: PathBuf,
+    spool: BufWriter<File>,
+    width: u32,
+    height: u32,
+    // The last frame and its first row not covered by the frame before it;
+    // its rows from the middle of the next overlap on aren't final yet
+    pending: Option<(RgbaImage, u32)>,
+}
+
+impl StreamingStitcher {
+    /// Starts a stitch that will be saved as a PNG at `path`
+    pub fn create(path: &Path) -> Result<Self> {
+        let mut spool_path = path.as_os_str().to_owned();
+        spool_path.push(".rows");
+        let spool_path = PathBuf::from(spool_path);
+        let spool = File::create(&spool_path)
+            .map_err(|e| anyhow::anyhow!("Failed to create {}: {}", spool_path.display(), e))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            spool_path,
+            spool: BufWriter::new(spool),
+            width: 0,
+            height: 0,
+            pending: None,
+        })
+    }
+
+    /// Adds the next frame, which repeats the previous frame's bottom
+    /// `overlap` rows (capped one row short of the shorter frame, as in
+    /// [`crate::stitch::clamp_overlaps`]). The overlap of the first frame is
+    /// ignored.
+    pub fn push(&mut self, frame: RgbaImage, overlap: u32) -> Result<()> {
+        let Some((previous, start)) = self.pending.take() else {
+            self.width = frame.width();
+            self.pending = Some((frame, 0));
+            return Ok(());
+        };
+
+        let overlap = overlap.min(previous.height().min(frame.height()).saturating_sub(1));
+        // Hard cut at the middle of the overlap
+        let end = previous.height() - overlap + overlap / 2;
+        self.write_rows(&previous, start..end)?;
+        self.pending = Some((frame, overlap / 2));
+        Ok(())
+    }
+
+    /// Writes the last frame and encodes the PNG, returning its size
+    pub fn finish(mut self) -> Result<(u32, u32)> {
+        if let Some((last, start)) = self.pending.take() {
+            self.write_rows(&last, start..last.height())?;
+        }
+        self.spool.flush()?;
+        if self.height == 0 {
+            return Err(anyhow::anyhow!("No frames were captured"));
+        }
+
+        let file = File::create(&self.path)
+            .map_err(|e| anyhow::anyhow!("Failed to create {}: {}", self.path.display(), e))?;
+        let mut encoder = png::Encoder::new(BufWriter::new(file), self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?.into_stream_writer()?;
+
+        let mut spool = BufReader::new(File::open(&self.spool_path)?);
+        let mut row = vec![0u8; self.width as usize * 4];
+        for _ in 0..self.height {
+            spool.read_exact(&mut row)?;
+            writer.write_all(&row)?;
+        }
+        writer.finish()?;
+
+        Ok((self.width, self.height))
+    }
+
+    /// Appends `rows` of `frame`, cut or padded with transparent pixels to
+    /// the first frame's width
+    fn write_rows(&mut self, frame: &RgbaImage, rows: std::ops::Range<u32>) -> Result<()> {
+        let frame_row = frame.width() as usize * 4;
+        let row_bytes = self.width as usize * 4;
+        let copied = frame_row.min(row_bytes);
+        let padding = vec![0u8; row_bytes - copied];
+
+        for y in rows {
+            let start = y as usize * frame_row;
+            self.spool
+                .write_all(&frame.as_raw()[start..start + copied])?;
+            self.spool.write_all(&padding)?;
+            self.height += 1;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for StreamingStitcher {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.spool_path);
+    }
+}
@@ -0,0 +1,39 @@
+//! Reads and writes presets through a temp file instead of the user's
+//! `~/.capture-presets.json`.
+
+use capture::presets::{self, Preset};
+use std::collections::HashMap;
+
+#[test]
+fn presets_round_trip_through_the_overridden_file() {
+    let path = std::env::temp_dir().join(format!("capture-presets-{}.json", std::process::id()));
+    presets::set_preset_file_path(Some(path.clone()));
+    assert_eq!(presets::get_preset_file_path().unwrap(), path);
+
+    // A missing file is just an empty preset list
+    assert!(presets::load_presets().unwrap().is_empty());
+
+    let news = Preset {
+        overlap: Some(150),
+        key: Some("pagedown".to_string()),
+        ..Preset::from_crop("0,80,full,full")
+    };
+    let saved = HashMap::from([
+        ("page".to_string(), Preset::from_crop("100,50,1920,1080")),
+        ("news".to_string(), news),
+    ]);
+    presets::save_presets(&saved).unwrap();
+
+    // Crop-only presets keep the plain string form older versions read
+    let written: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(written["page"], "100,50,1920,1080");
+
+    assert_eq!(presets::load_presets().unwrap(), saved);
+    let all = presets::get_all_presets().unwrap();
+    assert_eq!(all["news"], saved["news"]);
+    assert!(all.contains_key("1080p"));
+
+    std::fs::remove_file(&path).unwrap();
+    presets::set_preset_file_path(None);
+}
@@ -0,0 +1,251 @@
+//! Runs the scroll loop over scripted frames, so no screen or input
+//! permission is needed.
+
+use capture::{
+    CaptureError, CaptureOptions, MockSource, ScreenCapture, ScrollDirection, ScrollKey,
+};
+use image::{Rgba, RgbaImage};
+
+const FRAME_HEIGHT: u32 = 100;
+const SCROLL_STEP: u32 = 75;
+const OVERLAP: u32 = FRAME_HEIGHT - SCROLL_STEP;
+
+/// A page where every row has its own color, so any misplaced row shows
+fn page(height: u32) -> RgbaImage {
+    RgbaImage::from_fn(8, height, |x, y| {
+        Rgba([(y % 256) as u8, (y / 256) as u8, (x * 30) as u8, 255])
+    })
+}
+
+/// The frames a viewport of `FRAME_HEIGHT` rows sees while scrolling down
+/// `page` by `SCROLL_STEP` rows at a time
+fn scrolled_frames(page: &RgbaImage) -> Vec<RgbaImage> {
+    (0..=page.height() - FRAME_HEIGHT)
+        .step_by(SCROLL_STEP as usize)
+        .map(|top| image::imageops::crop_imm(page, 0, top, page.width(), FRAME_HEIGHT).to_image())
+        .collect()
+}
+
+fn options() -> CaptureOptions {
+    CaptureOptions::new()
+        .with_overlap(OVERLAP)
+        .with_delay(0)
+        .with_scroll_delay(0)
+}
+
+#[test]
+fn stitches_scrolled_frames_back_into_the_page() {
+    let page = page(400);
+    let frames = scrolled_frames(&page);
+    assert_eq!(frames.len(), 5);
+    let source = MockSource::new(frames);
+
+    let stitched = ScreenCapture::new()
+        .capture_from_source(&source, &options())
+        .unwrap();
+
+    assert_eq!(stitched.dimensions(), page.dimensions());
+    assert_eq!(stitched, page);
+    // The repeated last frame is what ends the capture
+    assert_eq!(source.frames_served(), 6);
+}
+
+#[test]
+fn stitches_horizontally_when_the_capture_says_so() {
+    // The vertical page turned on its side, scrolled right instead of down
+    let page = image::imageops::rotate270(&page(400));
+    let frames: Vec<RgbaImage> = scrolled_frames(&image::imageops::rotate90(&page))
+        .iter()
+        .map(image::imageops::rotate270)
+        .collect();
+    let source = MockSource::new(frames);
+
+    let stitched = ScreenCapture::new()
+        .with_direction(ScrollDirection::Horizontal)
+        .capture_from_source(&source, &options())
+        .unwrap();
+
+    assert_eq!(stitched, page);
+}
+
+#[test]
+fn percentage_overlap_resolves_against_the_frame_height() {
+    let page = page(400);
+    let source = MockSource::new(scrolled_frames(&page));
+
+    // 25% of the 100px frames is the real 25px overlap
+    let stitched = ScreenCapture::new()
+        .with_overlap_percent(Some(25.0))
+        .capture_from_source(&source, &options().with_overlap(0))
+        .unwrap();
+    assert_eq!(stitched, page);
+}
+
+#[test]
+fn streamed_output_matches_the_in_memory_stitch() {
+    let page = page(400);
+    let source = MockSource::new(scrolled_frames(&page));
+    let path = std::env::temp_dir().join(format!("capture-stream-{}.png", std::process::id()));
+
+    let returned = ScreenCapture::new()
+        .with_stream_output(Some(path.clone()))
+        .capture_from_source(&source, &options())
+        .unwrap();
+    let streamed = image::open(&path).unwrap().to_rgba8();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(returned.dimensions(), (0, 0));
+    assert_eq!(streamed, page);
+}
+
+#[test]
+fn max_scrolls_stops_early() {
+    let page = page(400);
+    let source = MockSource::new(scrolled_frames(&page));
+
+    let stitched = ScreenCapture::new()
+        .capture_from_source(&source, &options().with_max_scrolls(Some(2)))
+        .unwrap();
+
+    let expected_height = FRAME_HEIGHT + 2 * SCROLL_STEP;
+    assert_eq!(stitched.dimensions(), (8, expected_height));
+    assert_eq!(
+        stitched,
+        image::imageops::crop_imm(&page, 0, 0, 8, expected_height).to_image()
+    );
+}
+
+#[test]
+fn exact_frames_keeps_capturing_past_the_end_of_the_page() {
+    let page = page(400);
+    let source = MockSource::new(scrolled_frames(&page));
+    let capture = ScreenCapture::new().with_exact_frames(Some(7));
+
+    capture.capture_from_source(&source, &options()).unwrap();
+
+    // Five frames of page, then two repeats of the last one
+    assert_eq!(capture.last_capture_stats().unwrap().frames, 7);
+}
+
+#[test]
+fn single_unchanging_frame_is_returned_as_is() {
+    let frame = page(FRAME_HEIGHT);
+    let source = MockSource::new(vec![frame.clone()]);
+
+    let stitched = ScreenCapture::new()
+        .capture_from_source(&source, &options())
+        .unwrap();
+
+    assert_eq!(stitched, frame);
+}
+
+#[test]
+fn empty_source_is_an_error() {
+    let source = MockSource::new(Vec::new());
+    assert!(
+        ScreenCapture::new()
+            .capture_from_source(&source, &options())
+            .is_err()
+    );
+}
+
+#[test]
+fn zero_height_frame_is_a_clean_error() {
+    let source = MockSource::new(vec![RgbaImage::new(8, 0)]);
+    let result = ScreenCapture::new().capture_from_source(&source, &options());
+    assert!(matches!(
+        result,
+        Err(CaptureError::EmptyFrame { crop: None })
+    ));
+}
+
+#[test]
+fn stats_describe_the_last_capture() {
+    let page = page(400);
+    let source = MockSource::new(scrolled_frames(&page));
+    let capture = ScreenCapture::new();
+    assert!(capture.last_capture_stats().is_none());
+
+    capture.capture_from_source(&source, &options()).unwrap();
+
+    let stats = capture.last_capture_stats().unwrap();
+    assert_eq!(stats.frames, 5);
+    assert_eq!(stats.overlaps, vec![OVERLAP; 4]);
+    // Four scrolls that moved, then the identical frame that ended it
+    assert_eq!(stats.diff_percentages.len(), 4);
+    assert_eq!(stats.final_size, [8, 400]);
+}
+
+#[test]
+fn frames_yields_each_scroll_until_the_content_stops() {
+    let page = page(400);
+    let frames = scrolled_frames(&page);
+    let source = MockSource::new(frames.clone());
+    let capture = ScreenCapture::new();
+
+    let yielded: Vec<RgbaImage> = capture
+        .frames_from_source(&source, options())
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(yielded, frames);
+}
+
+#[test]
+fn frames_can_be_abandoned_early() {
+    let page = page(400);
+    let source = MockSource::new(scrolled_frames(&page));
+    let capture = ScreenCapture::new();
+
+    let first_two = capture
+        .frames_from_source(&source, options())
+        .take(2)
+        .count();
+
+    assert_eq!(first_two, 2);
+    assert_eq!(source.frames_served(), 2);
+}
+
+#[test]
+fn frames_follow_the_scroll_loop_settings() {
+    let page = page(400);
+    let source = MockSource::new(scrolled_frames(&page));
+    let capture = ScreenCapture::new().with_exact_frames(Some(7));
+
+    let yielded = capture.frames_from_source(&source, options()).count();
+
+    assert_eq!(yielded, 7);
+}
+
+#[test]
+fn max_duration_keeps_the_frames_captured_so_far() {
+    let page = page(400);
+    let frames = scrolled_frames(&page);
+    let source = MockSource::new(frames.clone());
+
+    let stitched = ScreenCapture::new()
+        .with_max_duration(Some(std::time::Duration::ZERO))
+        .capture_from_source(&source, &options())
+        .unwrap();
+
+    assert_eq!(stitched, frames[0]);
+}
+
+#[test]
+fn fallback_key_gets_past_a_stuck_scroll() {
+    let page = page(400);
+    let mut frames = scrolled_frames(&page);
+    // The main key fails to move once, halfway down
+    frames.insert(2, frames[1].clone());
+
+    let stuck = ScreenCapture::new()
+        .capture_from_source(&MockSource::new(frames.clone()), &options())
+        .unwrap();
+    assert_eq!(stuck.height(), FRAME_HEIGHT + SCROLL_STEP);
+
+    let stitched = ScreenCapture::new()
+        .with_fallback_key(Some(ScrollKey::PageDown.into()))
+        .capture_from_source(&MockSource::new(frames), &options())
+        .unwrap();
+    assert_eq!(stitched, page);
+}